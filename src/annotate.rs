@@ -1,11 +1,11 @@
 // -- submodules
-mod annotate_uitls;
+mod annotate_utils;
 mod classification;
 mod color;
 mod detection;
 mod font;
 mod obb;
-mod pose;
+pub mod pose;
 
 use classification::draw_classification;
 use detection::draw_detection;
@@ -14,6 +14,10 @@ use obb::draw_obb;
 use pose::draw_pose;
 use serde::Deserialize;
 
+// Pose rendering is also usable standalone (e.g. with a custom animal skeleton), so it's
+// exported rather than kept as an internal detail of `annotate_image`.
+pub use pose::{KPT_COLOR_INDICES, LIMB_COLOR_INDICES, SKELETON, draw_pose as draw_pose_skeleton};
+
 // -- external imports
 use crate::error::Result;
 use ab_glyph::FontRef;
@@ -37,6 +41,26 @@ pub struct AnnotateConfigs {
 
     /// (top-k) number of classification results to show
     pub top_k: Option<usize>,
+
+    /// Opacity of the segmentation mask overlay, in `[0.0, 1.0]`
+    pub mask_alpha: f32,
+
+    /// Whether to outline each segmentation mask's contour, in addition to the alpha overlay
+    pub show_mask_contour: bool,
+
+    /// Fill opacity for oriented bounding boxes, in `[0.0, 1.0]`. `0.0` draws an outline only.
+    pub obb_fill_alpha: f32,
+
+    /// Minimum confidence a keypoint (and both endpoints of a limb) must have to be drawn at
+    /// full strength
+    pub kpt_conf_threshold: f32,
+
+    /// Instead of omitting keypoints/limbs below `kpt_conf_threshold`, draw them faded (reduced
+    /// alpha and a smaller radius) so partially-visible joints stay visible rather than vanishing
+    pub show_low_conf_kpts: bool,
+
+    /// Draw each keypoint's index and confidence as a small text label next to it
+    pub show_kpt_labels: bool,
 }
 
 impl Default for AnnotateConfigs {
@@ -47,6 +71,12 @@ impl Default for AnnotateConfigs {
             show_label: true,
             show_conf: true,
             top_k: Some(5),
+            mask_alpha: 0.3,
+            show_mask_contour: false,
+            obb_fill_alpha: 0.0,
+            kpt_conf_threshold: pose::DEFAULT_KPT_CONF_THRESHOLD,
+            show_low_conf_kpts: false,
+            show_kpt_labels: false,
         }
     }
 }
@@ -55,6 +85,7 @@ pub fn annotate_image(
     img: &DynamicImage,
     result: &ul::Results,
     configs: &AnnotateConfigs,
+    track_ids: Option<&[u64]>,
 ) -> Result<DynamicImage> {
     let on_blank = configs.on_blank;
     let show_box = configs.show_box;
@@ -73,7 +104,7 @@ pub fn annotate_image(
     };
 
     // Prepare font if needed
-    let font_data = if show_label || have_obb || have_probs {
+    let font_data = if show_label || have_obb || have_probs || configs.show_kpt_labels {
         let mut use_unicode_font = false;
         if result.boxes.is_some() {
             for name in result.names.values() {
@@ -101,9 +132,9 @@ pub fn annotate_image(
     };
 
     // Draw annotations
-    draw_detection(&mut annotated, result, configs, font.as_ref());
-    draw_pose(&mut annotated, result, None, None, None);
-    draw_obb(&mut annotated, result, configs, font.as_ref());
+    draw_detection(&mut annotated, result, configs, font.as_ref(), track_ids);
+    draw_pose(&mut annotated, result, configs, font.as_ref(), None, None, None);
+    draw_obb(&mut annotated, result, configs, font.as_ref(), track_ids);
     draw_classification(&mut annotated, result, font.as_ref(), top_k.unwrap_or(5));
 
     Ok(DynamicImage::ImageRgb8(annotated))
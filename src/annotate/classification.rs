@@ -3,7 +3,7 @@ use image::{Rgb, RgbImage};
 use imageproc::drawing::draw_text_mut;
 use ultralytics_inference as ul;
 
-use super::annotate_uitls::draw_transparent_rect;
+use super::annotate_utils::draw_transparent_rect;
 
 /// Draw classification results
 pub fn draw_classification(
@@ -2,10 +2,11 @@ use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
 use image::RgbImage;
 use imageproc::drawing::{draw_filled_rect_mut, draw_hollow_rect_mut, draw_text_mut};
 use imageproc::rect::Rect;
+use rayon::prelude::*;
 use ultralytics_inference as ul;
 
 use super::AnnotateConfigs;
-use super::annotate_uitls::rect_intersect;
+use super::annotate_utils::{draw_line_segment, rect_intersect};
 use super::color::{get_class_color, get_text_color};
 
 /// Draw object detection results (boxes and masks)
@@ -14,76 +15,129 @@ pub fn draw_detection(
     result: &ul::Results,
     configs: &AnnotateConfigs,
     font: Option<&FontRef>,
+    track_ids: Option<&[u64]>,
 ) {
-    draw_masks(img, result);
-    draw_boxes_and_labels(img, result, configs, font);
+    draw_masks(img, result, configs.mask_alpha);
+    if configs.show_mask_contour {
+        draw_mask_contours(img, result);
+    }
+    draw_boxes_and_labels(img, result, configs, font, track_ids);
 }
 
-fn draw_masks(img: &mut RgbImage, result: &ul::Results) {
+/// Alpha-blend each instance's mask directly into `img`, touching only the pixels inside its
+/// (clamped) box region rather than the whole frame. Cost is proportional to detected area, not
+/// frame size, and each instance's box is filled row-by-row in parallel.
+fn draw_masks(img: &mut RgbImage, result: &ul::Results, mask_alpha: f32) {
     // Get boxes
     let boxes = match result.boxes.as_ref() {
         Some(b) => b,
         None => return, // No boxes to draw masks for
     };
 
+    let masks = match result.masks.as_ref() {
+        Some(m) => m,
+        None => return, // No masks to draw
+    };
+
     let (width, height) = img.dimensions();
     let xyxy = boxes.xyxy();
     let cls = boxes.cls();
+    let (mask_n, _mask_h, _mask_w) = masks.data.dim();
 
-    // Create an overlay image for masks to handle overlaps correctly
-    let mut overlay = img.clone();
-    let mut mask_present = false;
+    let alpha = mask_alpha.clamp(0.0, 1.0);
+    let inv_alpha = 1.0 - alpha;
+    let stride = width as usize * 3;
+    let buf: &mut [u8] = &mut *img;
 
-    // Draw masks onto the overlay
-    if let Some(masks) = result.masks.as_ref() {
-        let (mask_n, _mask_h, _mask_w) = masks.data.dim();
-
-        for i in 0..boxes.len() {
-            if i >= mask_n {
-                break;
-            }
-
-            let class_id = cls[i] as usize;
-            let color = get_class_color(class_id);
-            let (r, g, b) = (color.0[0], color.0[1], color.0[2]);
+    for i in 0..boxes.len().min(mask_n) {
+        let class_id = cls[i] as usize;
+        let color = get_class_color(class_id);
+        let (r, g, b) = (
+            f32::from(color.0[0]),
+            f32::from(color.0[1]),
+            f32::from(color.0[2]),
+        );
 
-            mask_present = true;
+        let x1 = xyxy[[i, 0]].max(0.0).min(width as f32) as usize;
+        let y1 = xyxy[[i, 1]].max(0.0).min(height as f32) as usize;
+        let x2 = xyxy[[i, 2]].max(0.0).min(width as f32) as usize;
+        let y2 = xyxy[[i, 3]].max(0.0).min(height as f32) as usize;
 
-            let x1 = xyxy[[i, 0]].max(0.0).min(width as f32) as u32;
-            let y1 = xyxy[[i, 1]].max(0.0).min(height as f32) as u32;
-            let x2 = xyxy[[i, 2]].max(0.0).min(width as f32) as u32;
-            let y2 = xyxy[[i, 3]].max(0.0).min(height as f32) as u32;
+        if x2 <= x1 || y2 <= y1 {
+            continue;
+        }
 
-            for y in y1..y2 {
+        let region = &mut buf[y1 * stride..y2 * stride];
+        region
+            .par_chunks_mut(stride)
+            .enumerate()
+            .for_each(|(row_offset, row)| {
+                let y = y1 + row_offset;
                 for x in x1..x2 {
-                    if masks.data[[i, y as usize, x as usize]] > 0.5 {
-                        let pixel = overlay.get_pixel_mut(x, y);
-                        pixel.0[0] = r;
-                        pixel.0[1] = g;
-                        pixel.0[2] = b;
+                    if masks.data[[i, y, x]] > 0.5 {
+                        let px = x * 3;
+                        row[px] = f32::from(row[px]).mul_add(inv_alpha, r * alpha) as u8;
+                        row[px + 1] = f32::from(row[px + 1]).mul_add(inv_alpha, g * alpha) as u8;
+                        row[px + 2] = f32::from(row[px + 2]).mul_add(inv_alpha, b * alpha) as u8;
                     }
                 }
+            });
+    }
+}
+
+/// Outline each instance's mask boundary, on top of the alpha overlay from [`draw_masks`]. Traced
+/// per-instance on a cropped region rather than the whole frame, same locality trade-off as
+/// `draw_masks`.
+fn draw_mask_contours(img: &mut RgbImage, result: &ul::Results) {
+    let boxes = match result.boxes.as_ref() {
+        Some(b) => b,
+        None => return,
+    };
+
+    let masks = match result.masks.as_ref() {
+        Some(m) => m,
+        None => return,
+    };
+
+    let (width, height) = img.dimensions();
+    let xyxy = boxes.xyxy();
+    let cls = boxes.cls();
+    let (mask_n, _mask_h, _mask_w) = masks.data.dim();
+
+    for i in 0..boxes.len().min(mask_n) {
+        let class_id = cls[i] as usize;
+        let color = get_class_color(class_id);
+
+        let x1 = xyxy[[i, 0]].max(0.0).min(width as f32) as u32;
+        let y1 = xyxy[[i, 1]].max(0.0).min(height as f32) as u32;
+        let x2 = xyxy[[i, 2]].max(0.0).min(width as f32) as u32;
+        let y2 = xyxy[[i, 3]].max(0.0).min(height as f32) as u32;
+
+        if x2 <= x1 || y2 <= y1 {
+            continue;
+        }
+
+        let mut region = image::GrayImage::new(x2 - x1, y2 - y1);
+        for y in y1..y2 {
+            for x in x1..x2 {
+                if masks.data[[i, y as usize, x as usize]] > 0.5 {
+                    region.put_pixel(x - x1, y - y1, image::Luma([255]));
+                }
             }
         }
-    }
 
-    // Blend overlay with original image
-    if mask_present {
-        let alpha = 0.3;
-        for y in 0..height {
-            for x in 0..width {
-                let p_img = img.get_pixel_mut(x, y);
-                let p_overlay = overlay.get_pixel(x, y);
-
-                p_img.0[0] = f32::from(p_overlay.0[0])
-                    .mul_add(alpha, f32::from(p_img.0[0]) * (1.0 - alpha))
-                    as u8;
-                p_img.0[1] = f32::from(p_overlay.0[1])
-                    .mul_add(alpha, f32::from(p_img.0[1]) * (1.0 - alpha))
-                    as u8;
-                p_img.0[2] = f32::from(p_overlay.0[2])
-                    .mul_add(alpha, f32::from(p_img.0[2]) * (1.0 - alpha))
-                    as u8;
+        let contours = imageproc::contours::find_contours_with_threshold::<i32>(&region, 1);
+        for contour in &contours {
+            for pair in contour.points.windows(2) {
+                draw_line_segment(
+                    img,
+                    (pair[0].x + x1 as i32) as f32,
+                    (pair[0].y + y1 as i32) as f32,
+                    (pair[1].x + x1 as i32) as f32,
+                    (pair[1].y + y1 as i32) as f32,
+                    color,
+                    1,
+                );
             }
         }
     }
@@ -94,6 +148,7 @@ fn draw_boxes_and_labels(
     result: &ul::Results,
     configs: &AnnotateConfigs,
     font: Option<&FontRef>,
+    track_ids: Option<&[u64]>,
 ) {
     let show_box = configs.show_box;
     let show_label = configs.show_label && show_box;
@@ -178,6 +233,11 @@ fn draw_boxes_and_labels(
         } else {
             class_name.to_string()
         };
+        let track_id = track_ids.and_then(|ids| ids.get(i)).filter(|&&id| id != 0);
+        let label = match track_id {
+            Some(id) => format!("ID:{id} {label}"),
+            None => label,
+        };
 
         if let Some(f) = font {
             let scale = PxScale::from(font_scale);
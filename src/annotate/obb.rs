@@ -5,7 +5,7 @@ use imageproc::rect::Rect;
 use ultralytics_inference as ul;
 
 use super::AnnotateConfigs;
-use super::annotate_uitls::{draw_line_segment, rect_intersect};
+use super::annotate_utils::{draw_rotated_rect, rect_intersect};
 use super::color::{get_class_color, get_text_color};
 
 /// Draw oriented bounding boxes (OBB)
@@ -14,8 +14,10 @@ pub fn draw_obb(
     result: &ul::Results,
     configs: &AnnotateConfigs,
     font: Option<&FontRef>,
+    track_ids: Option<&[u64]>,
 ) {
     let show_conf = configs.show_conf;
+    let fill_alpha = configs.obb_fill_alpha;
 
     let obb = match &result.obb {
         Some(o) => o,
@@ -43,14 +45,13 @@ pub fn draw_obb(
         let class_id = cls[i] as usize;
         let color = get_class_color(class_id);
 
-        for j in 0..4 {
-            let next_j = (j + 1) % 4;
-            let x1 = corners[[i, j, 0]];
-            let y1 = corners[[i, j, 1]];
-            let x2 = corners[[i, next_j, 0]];
-            let y2 = corners[[i, next_j, 1]];
-            draw_line_segment(img, x1, y1, x2, y2, color, thickness);
-        }
+        let quad = [
+            (corners[[i, 0, 0]], corners[[i, 0, 1]]),
+            (corners[[i, 1, 0]], corners[[i, 1, 1]]),
+            (corners[[i, 2, 0]], corners[[i, 2, 1]]),
+            (corners[[i, 3, 0]], corners[[i, 3, 1]]),
+        ];
+        draw_rotated_rect(img, quad, color, fill_alpha, thickness);
 
         let class_name = result.names.get(&class_id).map_or("object", String::as_str);
         let label = if show_conf {
@@ -58,6 +59,11 @@ pub fn draw_obb(
         } else {
             class_name.to_string()
         };
+        let track_id = track_ids.and_then(|ids| ids.get(i)).filter(|&&id| id != 0);
+        let label = match track_id {
+            Some(id) => format!("ID:{id} {label}"),
+            None => label,
+        };
 
         if let Some(f) = font {
             let scale = PxScale::from(font_scale);
@@ -69,10 +75,15 @@ pub fn draw_obb(
             let text_w = text_w.ceil() as i32;
             let text_h = scale.y.ceil() as i32;
 
+            // Anchor the label at the top-most corner of the rotated box, since none of the
+            // four corners is reliably "top-left" once the box is rotated.
+            let top_corner = (0..4)
+                .min_by(|&a, &b| corners[[i, a, 1]].total_cmp(&corners[[i, b, 1]]))
+                .unwrap_or(0);
+
             // Smart label placement
-            // Default: at the first corner (usually top-left-ish)
-            let mut text_x = corners[[i, 0, 0]] as i32;
-            let mut text_y = (corners[[i, 0, 1]] as i32 - text_h).max(0);
+            let mut text_x = corners[[i, top_corner, 0]] as i32;
+            let mut text_y = (corners[[i, top_corner, 1]] as i32 - text_h).max(0);
 
             // If label is out of image (left), move right
             if text_x < 0 {
@@ -115,7 +126,7 @@ pub fn draw_obb(
                 // Check bounds again
                 if text_y + text_h >= height as i32 {
                     // Reached bottom, try resetting y and moving x
-                    text_y = (corners[[i, 0, 1]] as i32 - text_h).max(0);
+                    text_y = (corners[[i, top_corner, 1]] as i32 - text_h).max(0);
                     text_x += 10; // Shift right
                     if text_x + text_w >= width as i32 {
                         break 'placement;
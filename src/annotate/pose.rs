@@ -1,7 +1,12 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
 use image::{Rgb, RgbImage};
+use imageproc::drawing::draw_text_mut;
 use ultralytics_inference as ul;
 
-use super::annotate_uitls::{draw_filled_circle, draw_line_segment};
+use super::AnnotateConfigs;
+use super::annotate_utils::{
+    draw_filled_circle, draw_filled_circle_alpha, draw_line_segment, draw_line_segment_alpha,
+};
 use super::color::POSE_COLORS;
 
 /// COCO-Pose dataset skeleton structure (pairs of keypoint indices)
@@ -40,12 +45,26 @@ pub const LIMB_COLOR_INDICES: [usize; 19] = [
 /// Mapping: arms=blue, legs=orange, face=green
 pub const KPT_COLOR_INDICES: [usize; 17] = [16, 16, 16, 16, 16, 9, 9, 9, 9, 9, 9, 0, 0, 0, 0, 0, 0];
 
+/// Default minimum keypoint confidence required before a point or limb is drawn
+pub const DEFAULT_KPT_CONF_THRESHOLD: f32 = 0.5;
+
+/// Alpha used to fade in a below-threshold keypoint/limb when `show_low_conf_kpts` is set,
+/// instead of omitting it entirely
+const LOW_CONF_ALPHA: f32 = 0.35;
+
+/// Radius scale-down applied to a below-threshold keypoint when `show_low_conf_kpts` is set
+const LOW_CONF_RADIUS_SCALE: f32 = 0.6;
+
 /// Draw pose estimation results (skeleton and keypoints)
 ///
 /// # Arguments
 ///
 /// * `img` - The image to draw on
 /// * `result` - The inference results containing keypoints
+/// * `configs` - Controls `kpt_conf_threshold`, whether low-confidence keypoints/limbs are faded
+///   in rather than omitted (`show_low_conf_kpts`), and whether each keypoint gets an
+///   `index:confidence` label (`show_kpt_labels`)
+/// * `font` - Font used for keypoint labels; required only if `configs.show_kpt_labels` is set
 /// * `skeleton` - Optional custom skeleton structure (pairs of keypoint indices). If `None`, uses
 ///   the default human pose skeleton from `SKELETON`.
 /// * `limb_colors` - Optional custom color indices for limbs. If `None`, uses the default from
@@ -57,7 +76,7 @@ pub const KPT_COLOR_INDICES: [usize; 17] = [16, 16, 16, 16, 16, 9, 9, 9, 9, 9, 9
 ///
 /// ```ignore
 /// // Use default human pose configuration
-/// draw_pose(&mut img, result, None, None, None);
+/// draw_pose(&mut img, result, &AnnotateConfigs::default(), None, None, None, None);
 ///
 /// // Use custom skeleton for animals
 /// const ANIMAL_SKELETON: [[usize; 2]; 10] = [...];
@@ -66,6 +85,8 @@ pub const KPT_COLOR_INDICES: [usize; 17] = [16, 16, 16, 16, 16, 9, 9, 9, 9, 9, 9
 /// draw_pose(
 ///     &mut img,
 ///     result,
+///     &AnnotateConfigs::default(),
+///     None,
 ///     Some(&ANIMAL_SKELETON),
 ///     Some(&ANIMAL_LIMB_COLORS),
 ///     Some(&ANIMAL_KPT_COLORS),
@@ -74,6 +95,8 @@ pub const KPT_COLOR_INDICES: [usize; 17] = [16, 16, 16, 16, 16, 9, 9, 9, 9, 9, 9
 pub fn draw_pose(
     img: &mut RgbImage,
     result: &ul::Results,
+    configs: &AnnotateConfigs,
+    font: Option<&FontRef>,
     skeleton: Option<&[[usize; 2]]>,
     limb_colors: Option<&[usize]>,
     kpt_colors: Option<&[usize]>,
@@ -83,6 +106,10 @@ pub fn draw_pose(
         None => return,
     };
 
+    let kpt_conf_threshold = configs.kpt_conf_threshold;
+    let show_low_conf = configs.show_low_conf_kpts;
+    let show_kpt_labels = configs.show_kpt_labels;
+
     let (width, height) = img.dimensions();
 
     // Calculate dynamic scale factor based on image size (reference 640x640)
@@ -92,6 +119,7 @@ pub fn draw_pose(
     // Scale thickness and radius
     let thickness = (1.0 * scale_factor).round().max(1.0) as i32;
     let radius = (3.0 * scale_factor).round() as i32;
+    let font_scale = (9.0 * scale_factor).max(8.0);
 
     // Use provided parameters or defaults
     let skeleton = skeleton.unwrap_or(&SKELETON);
@@ -115,10 +143,13 @@ pub fn draw_pose(
             let y2 = kpt_data[[person_idx, kpt_b, 1]];
             let conf2 = kpt_data[[person_idx, kpt_b, 2]];
 
-            if conf1 > 0.5 && conf2 > 0.5 {
-                let color_idx = limb_colors[limb_idx % limb_colors.len()];
-                let color = Rgb(POSE_COLORS[color_idx]);
+            let color_idx = limb_colors[limb_idx % limb_colors.len()];
+            let color = Rgb(POSE_COLORS[color_idx]);
+
+            if conf1 > kpt_conf_threshold && conf2 > kpt_conf_threshold {
                 draw_line_segment(img, x1, y1, x2, y2, color, thickness);
+            } else if show_low_conf && conf1 > 0.0 && conf2 > 0.0 {
+                draw_line_segment_alpha(img, x1, y1, x2, y2, color, thickness, LOW_CONF_ALPHA);
             }
         }
 
@@ -127,10 +158,38 @@ pub fn draw_pose(
             let y = kpt_data[[person_idx, kpt_idx, 1]];
             let conf = kpt_data[[person_idx, kpt_idx, 2]];
 
-            if conf > 0.5 && x >= 0.0 && y >= 0.0 && x < width as f32 && y < height as f32 {
-                let color_idx = kpt_colors[kpt_idx % kpt_colors.len()];
-                let color = Rgb(POSE_COLORS[color_idx]);
+            if x < 0.0 || y < 0.0 || x >= width as f32 || y >= height as f32 {
+                continue;
+            }
+
+            let visible = conf > kpt_conf_threshold;
+            if !visible && !(show_low_conf && conf > 0.0) {
+                continue;
+            }
+
+            let color_idx = kpt_colors[kpt_idx % kpt_colors.len()];
+            let color = Rgb(POSE_COLORS[color_idx]);
+
+            if visible {
                 draw_filled_circle(img, x as i32, y as i32, radius, color);
+            } else {
+                let faded_radius = ((radius as f32) * LOW_CONF_RADIUS_SCALE).round().max(1.0) as i32;
+                draw_filled_circle_alpha(img, x as i32, y as i32, faded_radius, color, LOW_CONF_ALPHA);
+            }
+
+            if show_kpt_labels
+                && let Some(f) = font
+            {
+                let label = format!("{kpt_idx}:{conf:.2}");
+                let scale = PxScale::from(font_scale);
+                let scaled_font = f.as_scaled(scale);
+                let text_w = label
+                    .chars()
+                    .map(|c| scaled_font.h_advance(scaled_font.glyph_id(c)))
+                    .sum::<f32>();
+                let text_x = (x as i32 + radius).min(width as i32 - text_w.ceil() as i32 - 1).max(0);
+                let text_y = (y as i32 - radius).max(0);
+                draw_text_mut(img, color, text_x, text_y, scale, f, &label);
             }
         }
     }
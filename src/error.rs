@@ -29,6 +29,9 @@ pub enum AppError {
 
     #[error("Invalid configuration: {0}")]
     Config(String),
+
+    #[error("Job was cancelled")]
+    Cancelled,
 }
 
 /// Result type with default AppError
@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use strum::{Display, EnumString, VariantNames};
+use ultralytics_inference as ul;
+
+use crate::error::{AppError, Result};
+
+/// Structured detection export format, written to `save_dir` alongside annotated images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display, VariantNames)]
+pub enum OutputFormat {
+    #[strum(serialize = "yolo")]
+    Yolo,
+    #[strum(serialize = "coco")]
+    Coco,
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "jsonl")]
+    Jsonl,
+}
+
+/// Custom deserializer with a helpful error message, mirroring `deserialize_infer_fn`.
+pub fn deserialize_output_format<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<OutputFormat>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(value) => OutputFormat::from_str(&value).map(Some).map_err(|_| {
+            let expected = crate::fuzzy::expected_variants(&value, OutputFormat::VARIANTS);
+            serde::de::Error::invalid_value(serde::de::Unexpected::Str(&value), &expected.as_str())
+        }),
+    }
+}
+
+/// Top-k classes considered when exporting a classification-only result (no boxes). Matches
+/// `AnnotateConfigs::top_k`'s default.
+const DEFAULT_EXPORT_TOP_K: usize = 5;
+
+/// One record, flattened for the `csv`/`jsonl` formats. Covers detection, segmentation, pose and
+/// classification results uniformly: `x1..y2` are omitted for a classification-only record (no
+/// `boxes`), and `keypoints` is present only when the frame's result has `keypoints`.
+#[derive(Serialize)]
+struct DetectionRecord<'a> {
+    frame: &'a str,
+    class_id: usize,
+    class_name: &'a str,
+    confidence: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x1: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    y1: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x2: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    y2: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keypoints: Option<Vec<[f32; 3]>>,
+}
+
+#[derive(Serialize)]
+struct CocoImage {
+    id: usize,
+    file_name: String,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Serialize)]
+struct CocoAnnotation {
+    id: usize,
+    image_id: usize,
+    category_id: usize,
+    bbox: [f32; 4],
+    score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation: Option<Vec<Vec<f32>>>,
+    /// Flat `[x0, y0, c0, x1, y1, c1, ...]` keypoints in COCO's flat layout, when the frame has
+    /// pose data. Each `c` is the model's keypoint confidence, not COCO's 0/1/2 visibility flag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keypoints: Option<Vec<f32>>,
+}
+
+#[derive(Serialize)]
+struct CocoCategory {
+    id: usize,
+    name: String,
+}
+
+#[derive(Serialize, Default)]
+struct CocoDoc {
+    images: Vec<CocoImage>,
+    annotations: Vec<CocoAnnotation>,
+    categories: Vec<CocoCategory>,
+}
+
+/// Writes each processed frame's detections to `save_dir` in the configured `OutputFormat`,
+/// streaming per frame rather than buffering the whole run.
+///
+/// `Coco` is the one exception: COCO is a single JSON document describing every image and
+/// annotation, so it's accumulated in memory as frames arrive and flushed once via
+/// [`Exporter::finish`] when the run completes.
+pub struct Exporter {
+    format: OutputFormat,
+    csv_file: Option<File>,
+    jsonl_file: Option<File>,
+    coco: CocoDoc,
+    known_categories: HashMap<usize, ()>,
+}
+
+impl Exporter {
+    /// Open (or create) whatever sidecar file the format needs under `save_dir`.
+    pub fn new(format: OutputFormat, save_dir: &Path) -> Result<Self> {
+        let (csv_file, jsonl_file) = match format {
+            OutputFormat::Csv => (Some(Self::create(save_dir, "detections.csv")?), None),
+            OutputFormat::Jsonl => (None, Some(Self::create(save_dir, "detections.jsonl")?)),
+            OutputFormat::Yolo | OutputFormat::Coco => (None, None),
+        };
+
+        let mut exporter = Self {
+            format,
+            csv_file,
+            jsonl_file,
+            coco: CocoDoc::default(),
+            known_categories: HashMap::new(),
+        };
+
+        if format == OutputFormat::Csv
+            && let Some(file) = exporter.csv_file.as_mut()
+        {
+            writeln!(file, "frame,class_id,class_name,confidence,x1,y1,x2,y2,keypoints")
+                .map_err(AppError::Io)?;
+        }
+
+        Ok(exporter)
+    }
+
+    fn create(save_dir: &Path, name: &str) -> Result<File> {
+        File::create(save_dir.join(name)).map_err(AppError::Io)
+    }
+
+    /// Write one frame's detections. Called from the save thread once per processed frame.
+    ///
+    /// Handles detection, segmentation and pose results (all carry `boxes`) uniformly; a
+    /// classification-only result (no `boxes`, just `probs`) is exported separately via
+    /// [`Self::write_classification_frame`], since `Yolo`/`Coco` have no established convention
+    /// for a boxless classification record.
+    pub fn write_frame(
+        &mut self,
+        save_dir: &Path,
+        frame_stem: &str,
+        result: &ul::Results,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        let Some(boxes) = result.boxes.as_ref() else {
+            return self.write_classification_frame(frame_stem, result);
+        };
+
+        let xyxy = boxes.xyxy();
+        let conf = boxes.conf();
+        let cls = boxes.cls();
+        let keypoints = result.keypoints.as_ref();
+
+        match self.format {
+            OutputFormat::Yolo => {
+                let mut file = Self::create(save_dir, &format!("{frame_stem}.txt"))?;
+                for i in 0..boxes.len() {
+                    let (x1, y1, x2, y2) = (xyxy[[i, 0]], xyxy[[i, 1]], xyxy[[i, 2]], xyxy[[i, 3]]);
+                    let w = (x2 - x1) / width as f32;
+                    let h = (y2 - y1) / height as f32;
+                    let cx = (x1 + x2) / 2.0 / width as f32;
+                    let cy = (y1 + y2) / 2.0 / height as f32;
+                    write!(
+                        file,
+                        "{} {:.6} {:.6} {:.6} {:.6} {:.6}",
+                        cls[i] as usize,
+                        cx,
+                        cy,
+                        w,
+                        h,
+                        conf[i]
+                    )
+                    .map_err(AppError::Io)?;
+                    if let Some(kpts) = keypoints.and_then(|k| Self::person_keypoints(k, i)) {
+                        for [kx, ky, kconf] in kpts {
+                            write!(file, " {:.6} {:.6} {:.6}", kx / width as f32, ky / height as f32, kconf)
+                                .map_err(AppError::Io)?;
+                        }
+                    }
+                    writeln!(file).map_err(AppError::Io)?;
+                }
+            }
+            OutputFormat::Csv => {
+                if let Some(file) = self.csv_file.as_mut() {
+                    for i in 0..boxes.len() {
+                        let class_id = cls[i] as usize;
+                        let class_name = result.names.get(&class_id).map_or("object", String::as_str);
+                        // Quoted, since the JSON array itself contains commas.
+                        let kpts_field = keypoints
+                            .and_then(|k| Self::person_keypoints(k, i))
+                            .map(|kpts| {
+                                format!("\"{}\"", serde_json::to_string(&kpts).unwrap_or_default())
+                            })
+                            .unwrap_or_default();
+                        writeln!(
+                            file,
+                            "{},{},{},{:.4},{:.2},{:.2},{:.2},{:.2},{}",
+                            frame_stem,
+                            class_id,
+                            class_name,
+                            conf[i],
+                            xyxy[[i, 0]],
+                            xyxy[[i, 1]],
+                            xyxy[[i, 2]],
+                            xyxy[[i, 3]],
+                            kpts_field,
+                        )
+                        .map_err(AppError::Io)?;
+                    }
+                }
+            }
+            OutputFormat::Jsonl => {
+                if let Some(file) = self.jsonl_file.as_mut() {
+                    for i in 0..boxes.len() {
+                        let class_id = cls[i] as usize;
+                        let class_name = result.names.get(&class_id).map_or("object", String::as_str);
+                        let record = DetectionRecord {
+                            frame: frame_stem,
+                            class_id,
+                            class_name,
+                            confidence: conf[i],
+                            x1: Some(xyxy[[i, 0]]),
+                            y1: Some(xyxy[[i, 1]]),
+                            x2: Some(xyxy[[i, 2]]),
+                            y2: Some(xyxy[[i, 3]]),
+                            keypoints: keypoints.and_then(|k| Self::person_keypoints(k, i)),
+                        };
+                        let line = serde_json::to_string(&record)
+                            .map_err(|e| AppError::Config(e.to_string()))?;
+                        writeln!(file, "{line}").map_err(AppError::Io)?;
+                    }
+                }
+            }
+            OutputFormat::Coco => {
+                let image_id = self.coco.images.len();
+                self.coco.images.push(CocoImage {
+                    id: image_id,
+                    file_name: format!("{frame_stem}.png"),
+                    width,
+                    height,
+                });
+
+                let masks = result.masks.as_ref();
+                for i in 0..boxes.len() {
+                    let class_id = cls[i] as usize;
+                    self.known_categories.entry(class_id).or_insert(());
+
+                    let x1 = xyxy[[i, 0]];
+                    let y1 = xyxy[[i, 1]];
+                    let x2 = xyxy[[i, 2]];
+                    let y2 = xyxy[[i, 3]];
+
+                    let segmentation = masks.and_then(|m| Self::mask_polygon(m, i, width, height));
+                    let kpts = keypoints
+                        .and_then(|k| Self::person_keypoints(k, i))
+                        .map(|kpts| kpts.into_iter().flatten().collect());
+
+                    self.coco.annotations.push(CocoAnnotation {
+                        id: self.coco.annotations.len(),
+                        image_id,
+                        category_id: class_id,
+                        bbox: [x1, y1, x2 - x1, y2 - y1],
+                        score: conf[i],
+                        segmentation,
+                        keypoints: kpts,
+                    });
+                }
+
+                self.coco.categories = self
+                    .known_categories
+                    .keys()
+                    .map(|&id| CocoCategory {
+                        id,
+                        name: result.names.get(&id).cloned().unwrap_or_else(|| id.to_string()),
+                    })
+                    .collect();
+                self.coco.categories.sort_by_key(|c| c.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Export a classification-only result (no `boxes`) as one record per top-k class. Only
+    /// meaningful for `Csv`/`Jsonl`; `Yolo`/`Coco` have no boxless-classification convention, so
+    /// they're silently skipped rather than writing something misleading.
+    fn write_classification_frame(&mut self, frame_stem: &str, result: &ul::Results) -> Result<()> {
+        let Some(probs) = result.probs.as_ref() else {
+            return Ok(());
+        };
+
+        for class_id in probs.top_k(DEFAULT_EXPORT_TOP_K) {
+            let confidence = probs.data[class_id];
+            let class_name = result.names.get(&class_id).map_or("class", String::as_str);
+
+            match self.format {
+                OutputFormat::Csv => {
+                    if let Some(file) = self.csv_file.as_mut() {
+                        writeln!(file, "{frame_stem},{class_id},{class_name},{confidence:.4},,,,,")
+                            .map_err(AppError::Io)?;
+                    }
+                }
+                OutputFormat::Jsonl => {
+                    if let Some(file) = self.jsonl_file.as_mut() {
+                        let record = DetectionRecord {
+                            frame: frame_stem,
+                            class_id,
+                            class_name,
+                            confidence,
+                            x1: None,
+                            y1: None,
+                            x2: None,
+                            y2: None,
+                            keypoints: None,
+                        };
+                        let line = serde_json::to_string(&record)
+                            .map_err(|e| AppError::Config(e.to_string()))?;
+                        writeln!(file, "{line}").map_err(AppError::Io)?;
+                    }
+                }
+                OutputFormat::Yolo | OutputFormat::Coco => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Instance `i`'s keypoints as `[x, y, confidence]` triples, or `None` if `i` is out of range.
+    fn person_keypoints(keypoints: &ul::Keypoints, i: usize) -> Option<Vec<[f32; 3]>> {
+        let (n_persons, n_kpts, _) = keypoints.data.dim();
+        if i >= n_persons {
+            return None;
+        }
+        Some(
+            (0..n_kpts)
+                .map(|k| {
+                    [
+                        keypoints.data[[i, k, 0]],
+                        keypoints.data[[i, k, 1]],
+                        keypoints.data[[i, k, 2]],
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    /// Trace the largest contour of instance `i`'s binary mask and return it as a flat
+    /// `[x0, y0, x1, y1, ...]` polygon in absolute image coordinates, COCO-style.
+    fn mask_polygon(masks: &ul::Masks, i: usize, width: u32, height: u32) -> Option<Vec<Vec<f32>>> {
+        let (mask_n, mask_h, mask_w) = masks.data.dim();
+        if i >= mask_n {
+            return None;
+        }
+
+        let mut gray = image::GrayImage::new(mask_w as u32, mask_h as u32);
+        for y in 0..mask_h {
+            for x in 0..mask_w {
+                if masks.data[[i, y, x]] > 0.5 {
+                    gray.put_pixel(x as u32, y as u32, image::Luma([255]));
+                }
+            }
+        }
+
+        let contours = imageproc::contours::find_contours_with_threshold::<i32>(&gray, 1);
+        let contour = contours.iter().max_by_key(|c| c.points.len())?;
+
+        let scale_x = width as f32 / mask_w as f32;
+        let scale_y = height as f32 / mask_h as f32;
+        let polygon: Vec<f32> = contour
+            .points
+            .iter()
+            .flat_map(|p| [p.x as f32 * scale_x, p.y as f32 * scale_y])
+            .collect();
+
+        Some(vec![polygon])
+    }
+
+    /// Flush any buffered state (the `Coco` document) to disk. Called once after the pipeline
+    /// finishes; a no-op for the streaming formats, whose files are already fully written.
+    pub fn finish(&mut self, save_dir: &Path) -> Result<()> {
+        if self.format != OutputFormat::Coco {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(&self.coco).map_err(|e| AppError::Config(e.to_string()))?;
+        std::fs::write(save_dir.join("detections.coco.json"), json).map_err(AppError::Io)
+    }
+}
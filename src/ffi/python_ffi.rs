@@ -1,22 +1,205 @@
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, RgbImage};
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArray3, PyReadonlyArray3};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
 use pyo3::prelude::*;
 use pyo3_stub_gen::define_stub_info_gatherer;
 use pyo3_stub_gen::derive::*;
+use ultralytics_inference as ul;
 
-use crate::{init_logger, parse_toml, run_prediction};
+use crate::infer_fn::InferResult;
+use crate::{AppError, PredictArgs, Source, init_logger, parse_toml, run_online_prediction, run_prediction};
 
 #[pyfunction]
 #[gen_stub_pyfunction]
 pub fn predict_from_toml(config_toml: &str) {
     init_logger();
-    let args =
-        parse_toml(&std::path::PathBuf::from(config_toml)).expect("Failed to parse TOML config");
+    let toml_path = PathBuf::from(config_toml);
+    let project_root = toml_path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let args = parse_toml(&toml_path, &project_root).expect("Failed to parse TOML config");
     run_prediction(&args).expect("Prediction failed");
 }
 
+/// A loaded YOLO model, kept open across calls so repeated `predict()` calls reuse the same
+/// session instead of reloading the model from disk every time.
+#[gen_stub_pyclass]
+#[pyclass(name = "YoloModel")]
+pub struct YoloModel {
+    model: ul::YOLOModel,
+    args: PredictArgs,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl YoloModel {
+    #[new]
+    #[pyo3(signature = (model_path, device=None, half=false, conf=0.25, iou=0.45))]
+    fn new(model_path: &str, device: Option<String>, half: bool, conf: f32, iou: f32) -> PyResult<Self> {
+        let args = PredictArgs {
+            model: PathBuf::from(model_path),
+            device,
+            half,
+            conf,
+            iou,
+            return_result: true,
+            ..Default::default()
+        };
+
+        let config: ul::InferenceConfig = (&args)
+            .try_into()
+            .map_err(|e: AppError| PyRuntimeError::new_err(e.to_string()))?;
+        let model = ul::YOLOModel::load_with_config(&args.model, config)
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to load model: {e}")))?;
+
+        Ok(Self { model, args })
+    }
+
+    /// Run inference on a single image, given either a file path or an `(H, W, 3)` `uint8` RGB
+    /// NumPy array.
+    fn predict(&mut self, image: &Bound<'_, PyAny>) -> PyResult<Vec<PyInferResult>> {
+        let source = image_source_from_py(image)?;
+        let results = run_online_prediction(&mut self.model, &source, &self.args)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?
+            .unwrap_or_default();
+        Ok(results.into_iter().map(PyInferResult::from).collect())
+    }
+}
+
+/// Build an in-memory `Source::Image` from whatever the caller handed `predict()`.
+fn image_source_from_py(image: &Bound<'_, PyAny>) -> PyResult<Source> {
+    if let Ok(path) = image.extract::<String>() {
+        let img = image::open(&path)
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to load image {path:?}: {e}")))?;
+        return Ok(Source::Image(img));
+    }
+
+    let array: PyReadonlyArray3<u8> = image.extract().map_err(|_| {
+        PyTypeError::new_err(
+            "predict() expects a file path (str) or an (H, W, 3) uint8 NumPy array",
+        )
+    })?;
+    let view = array.as_array();
+    let (height, width, channels) = view.dim();
+    if channels != 3 {
+        return Err(PyTypeError::new_err(format!(
+            "expected an (H, W, 3) RGB array, got channel count {channels}"
+        )));
+    }
+
+    let data: Vec<u8> = view.iter().copied().collect();
+    let rgb = RgbImage::from_raw(width as u32, height as u32, data)
+        .ok_or_else(|| PyRuntimeError::new_err("Failed to build image from array data"))?;
+    Ok(Source::Image(DynamicImage::ImageRgb8(rgb)))
+}
+
+/// One frame's detections, surfaced as NumPy arrays so results can flow straight into an
+/// existing Python CV pipeline without going through TOML files or image folders on disk.
+#[gen_stub_pyclass]
+#[pyclass(name = "InferResult")]
+pub struct PyInferResult {
+    inner: InferResult,
+}
+
+impl From<InferResult> for PyInferResult {
+    fn from(inner: InferResult) -> Self {
+        Self { inner }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyInferResult {
+    /// Box corners as an `(N, 4)` `[x1, y1, x2, y2]` array, or `None` if this result has no
+    /// detection boxes.
+    fn xyxy<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray2<f32>>> {
+        self.inner
+            .result
+            .boxes
+            .as_ref()
+            .map(|b| b.xyxy().to_owned().into_pyarray(py))
+    }
+
+    /// Per-detection confidence, aligned with `xyxy()`.
+    fn conf<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray1<f32>>> {
+        self.inner
+            .result
+            .boxes
+            .as_ref()
+            .map(|b| b.conf().to_owned().into_pyarray(py))
+    }
+
+    /// Per-detection class id, aligned with `xyxy()`.
+    fn cls<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray1<f32>>> {
+        self.inner
+            .result
+            .boxes
+            .as_ref()
+            .map(|b| b.cls().to_owned().into_pyarray(py))
+    }
+
+    /// Class names aligned with `cls()`, resolved through the model's class name table.
+    fn class_names(&self) -> Option<Vec<String>> {
+        let boxes = self.inner.result.boxes.as_ref()?;
+        let names = &self.inner.result.names;
+        Some(
+            boxes
+                .cls()
+                .iter()
+                .map(|&id| {
+                    names
+                        .get(&(id as usize))
+                        .cloned()
+                        .unwrap_or_else(|| "object".to_string())
+                })
+                .collect(),
+        )
+    }
+
+    /// Per-instance segmentation masks as an `(N, H, W)` array, or `None` if the model doesn't
+    /// output masks.
+    fn masks<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray3<f32>>> {
+        self.inner
+            .result
+            .masks
+            .as_ref()
+            .map(|m| m.data.clone().into_pyarray(py))
+    }
+
+    /// Per-instance keypoints as an `(N, K, 3)` `[x, y, conf]` array, or `None` if the model
+    /// doesn't output keypoints.
+    fn keypoints<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray3<f32>>> {
+        self.inner
+            .result
+            .keypoints
+            .as_ref()
+            .map(|k| k.data.clone().into_pyarray(py))
+    }
+
+    /// Oriented bounding box corners as an `(N, 4, 2)` array, or `None` if the model doesn't
+    /// output OBBs.
+    fn obb_xyxyxyxy<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray3<f32>>> {
+        self.inner
+            .result
+            .obb
+            .as_ref()
+            .map(|o| o.xyxyxyxy().to_owned().into_pyarray(py))
+    }
+
+    /// Persistent track id for each detection (`0` means "not tracked", see
+    /// [`crate::Tracker::update`]). `YoloModel` runs without `PredictArgs::tracking`, so this is
+    /// always `None` for its results.
+    fn track_ids(&self) -> Option<Vec<u64>> {
+        self.inner.track_ids.clone()
+    }
+}
+
 /// Export rust library as Python module.
 #[pymodule]
 fn yolo_inference(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(predict_from_toml, m)?)?;
+    m.add_class::<YoloModel>()?;
+    m.add_class::<PyInferResult>()?;
     Ok(())
 }
 
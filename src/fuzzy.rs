@@ -0,0 +1,54 @@
+//! Tiny Levenshtein-distance helper for "did you mean?" suggestions on invalid config enum
+//! values (`InferFn`, `OutputFormat`, ...).
+
+/// Classic DP edit distance between `a` and `b`, case-sensitive.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (len_a, len_b) = (a.len(), b.len());
+
+    let mut dp = vec![vec![0usize; len_b + 1]; len_a + 1];
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=len_b {
+        dp[0][j] = j;
+    }
+
+    for i in 1..=len_a {
+        for j in 1..=len_b {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            dp[i][j] = (dp[i - 1][j] + 1)
+                .min(dp[i][j - 1] + 1)
+                .min(dp[i - 1][j - 1] + cost);
+        }
+    }
+
+    dp[len_a][len_b]
+}
+
+/// Pick the closest match to `input` among `variants` (case-insensitive), if it's close enough
+/// to be a plausible typo rather than nonsense input: edit distance `<= 3`, or at most half the
+/// matched variant's length.
+pub(crate) fn suggest<'a>(input: &str, variants: &'a [&'a str]) -> Option<&'a str> {
+    let input = input.to_lowercase();
+    variants
+        .iter()
+        .map(|&variant| (variant, levenshtein(&input, &variant.to_lowercase())))
+        .min_by_key(|&(_, distance)| distance)
+        .filter(|&(variant, distance)| distance <= 3 || distance * 2 <= variant.len())
+        .map(|(variant, _)| variant)
+}
+
+/// Build the `invalid_value` "expected" string for a `strum`-backed enum, appending a "did you
+/// mean" suggestion when one is close enough.
+pub(crate) fn expected_variants(input: &str, variants: &[&str]) -> String {
+    match suggest(input, variants) {
+        Some(suggestion) => format!(
+            "one of {} (did you mean \"{}\"?)",
+            variants.join(", "),
+            suggestion
+        ),
+        None => format!("one of {}", variants.join(", ")),
+    }
+}
@@ -53,11 +53,8 @@ where
 {
     let value = String::deserialize(deserializer)?;
     InferFn::from_str(&value).map_err(|_| {
-        let variants = InferFn::VARIANTS;
-        serde::de::Error::invalid_value(
-            serde::de::Unexpected::Str(&value),
-            &format!("one of {}", variants.join(", ")).as_str(),
-        )
+        let expected = crate::fuzzy::expected_variants(&value, InferFn::VARIANTS);
+        serde::de::Error::invalid_value(serde::de::Unexpected::Str(&value), &expected.as_str())
     })
 }
 
@@ -74,6 +71,10 @@ pub struct InferResult {
 
     /// Source meta information
     pub meta: SourceMeta,
+
+    /// Persistent track ID per detection (same order as `result.boxes`), if `PredictArgs::tracking`
+    /// was enabled. `0` marks a detection too low-confidence to track (see [`crate::Tracker::update`]).
+    pub track_ids: Option<Vec<u64>>,
 }
 
 // -- public API
@@ -1,17 +1,20 @@
-use image::DynamicImage;
+use image::{DynamicImage, GenericImageView};
 use indicatif::{ProgressBar, ProgressFinish};
 use std::sync::mpsc;
 use std::thread;
+use std::time::Instant;
 use ultralytics_inference as ul;
 
 use crate::annotate::annotate_image;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::export::Exporter;
+use crate::job::{self, JobStage};
 use crate::predict::PredictArgs;
 use crate::progress_bar::progress_bar_style;
 use crate::source::{BatchSourceLoader, SourceMeta};
 
 use super::InferResult;
-use super::batch_utils::{batch_infer_fallback, get_batch_frame_names};
+use super::batch_utils::{AdaptiveBatchSizer, batch_infer_fallback, get_batch_frame_names};
 
 /// Channel-based concurrent pipeline for batch inference
 ///
@@ -30,38 +33,66 @@ pub fn batch_channel_pipeline_infer(
     let batch_size = args.batch.unwrap_or(1);
     let verbose = args.verbose;
     let save = annotate && save_dir.is_some();
+    let job = args.job.clone();
+
+    let adaptive_batch = args.adaptive_batch;
+    let batch_min = args.batch_min.unwrap_or(1).max(1);
+    let batch_max = args.batch_max.unwrap_or(batch_size).max(batch_min);
 
     tracing::info!("Running channel-based batch pipeline inference...");
     tracing::info!("[Source]: {:?}", args.source);
     tracing::info!("Batch Size: {}", batch_size);
+    if adaptive_batch {
+        tracing::info!("Adaptive batch sizing enabled, range [{}, {}]", batch_min, batch_max);
+    }
+
+    // A checkpoint file from a previous, interrupted run means this is a resume: keep the
+    // existing outputs and skip any frame already recorded as done instead of clearing the
+    // save directory. This only depends on `save_dir` being set, not on `annotate`, since an
+    // export-only run (annotate = false, output_format set) checkpoints too.
+    let resuming = save_dir
+        .as_ref()
+        .is_some_and(|dir| dir.join(job::CHECKPOINT_FILE).exists());
+    let completed_stems = if resuming {
+        tracing::info!("Found existing checkpoint, resuming interrupted run...");
+        save_dir.as_ref().map(|dir| job::load_checkpoint(dir)).unwrap_or_default()
+    } else {
+        Default::default()
+    };
 
     if let Some(dir) = save_dir {
-        if dir.is_dir() {
+        if dir.is_dir() && !resuming {
             tracing::warn!("Clearing existing save directory: {:?}", dir);
             std::fs::remove_dir_all(dir).expect("Failed to clear existing save directory");
         }
         std::fs::create_dir_all(dir).expect("Failed to create save directory");
     }
 
-    let loader = BatchSourceLoader::new(source, Some(batch_size));
+    let mut exporter = match (args.output_format, save_dir) {
+        (Some(format), Some(dir)) => Some(Exporter::new(format, dir)?),
+        _ => None,
+    };
+
+    let loader = BatchSourceLoader::new(source, Some(batch_size))?;
     let total_batches = loader.len();
     let total_frames = loader.total_frames();
     tracing::info!("Total batches to process: {}", total_batches);
-    tracing::info!("Total frames to process: {}", total_frames);
+    match total_frames {
+        Some(n) => tracing::info!("Total frames to process: {}", n),
+        None => tracing::info!("Total frames to process: unknown (streaming source)"),
+    }
     tracing::info!("-----------------------------------------");
 
     // preserve space in return_results if provided
     if let Some(vec) = return_results.as_mut() {
         vec.clear();
-        vec.reserve(total_frames);
+        vec.reserve(total_frames.unwrap_or(0));
     }
 
-    let pseudo_paths = vec!["".to_string(); batch_size];
-
     // Define data types for each pipeline stage
     type LoadStage = (usize, Vec<DynamicImage>, Vec<SourceMeta>);
     type InferStage = (usize, DynamicImage, ul::Results, SourceMeta);
-    type AnnotateStage = (usize, Option<DynamicImage>, ul::Results, SourceMeta);
+    type AnnotateStage = (usize, Option<DynamicImage>, ul::Results, SourceMeta, (u32, u32));
     type SaveStage = (usize, Option<DynamicImage>, ul::Results, SourceMeta);
 
     // Create channels for each stage with bounded capacity
@@ -70,20 +101,65 @@ pub fn batch_channel_pipeline_infer(
     let (annotate_tx, annotate_rx) = mpsc::sync_channel::<AnnotateStage>(channel_capacity);
     let (save_tx, save_rx) = mpsc::sync_channel::<SaveStage>(channel_capacity);
 
-    // initialize progress bar
-    let pb = ProgressBar::new(total_frames as u64)
-        .with_style(progress_bar_style())
-        .with_message("Running inference")
-        .with_finish(ProgressFinish::WithMessage("Finished".into()));
+    // initialize progress bar (falls back to an unbounded spinner for a streaming source)
+    let pb = match total_frames {
+        Some(n) => ProgressBar::new(n as u64).with_style(progress_bar_style()),
+        None => ProgressBar::new_spinner(),
+    }
+    .with_message("Running inference")
+    .with_finish(ProgressFinish::WithMessage("Finished".into()));
 
     // record if batch inference has failed before
     let mut infer_failed = false;
 
+    // Feedback channel: the inference thread reports the next batch size to request after
+    // measuring each batch's throughput; the loading thread applies it to the loader.
+    let (size_tx, size_rx) = mpsc::channel::<usize>();
+    let mut sizer = AdaptiveBatchSizer::new(batch_size, batch_min, batch_max);
+
     // Use scoped threads to allow borrowing model
     thread::scope(|s| {
         // Stage 1: Image Loading thread
+        let load_job = job.clone();
         let load_handle = s.spawn(move || {
-            for (batch_idx, (batch_images, batch_metas)) in loader.enumerate() {
+            load_job.set_stage(JobStage::Loading);
+            let mut loader = loader;
+            let mut batch_idx = 0usize;
+
+            loop {
+                if adaptive_batch {
+                    // Apply only the latest pending size; older ones are stale.
+                    let mut latest = None;
+                    while let Ok(new_size) = size_rx.try_recv() {
+                        latest = Some(new_size);
+                    }
+                    if let Some(new_size) = latest {
+                        loader.set_batch_size(new_size);
+                    }
+                }
+
+                let Some((batch_images, batch_metas)) = loader.next() else {
+                    break;
+                };
+
+                load_job.wait_while_paused();
+                if load_job.is_cancelled() {
+                    tracing::info!("Job cancelled, stopping loading after batch {}.", batch_idx);
+                    break;
+                }
+
+                // Skip frames already completed in a previous, interrupted run.
+                let (batch_images, batch_metas): (Vec<_>, Vec<_>) = batch_images
+                    .into_iter()
+                    .zip(batch_metas)
+                    .filter(|(_, meta)| !completed_stems.contains(&meta.frame_stem()))
+                    .unzip();
+
+                if batch_images.is_empty() {
+                    batch_idx += 1;
+                    continue;
+                }
+
                 if verbose {
                     let batch_frame_names = get_batch_frame_names(&batch_metas);
                     tracing::debug!("[Loading] batch {}: {:?}", batch_idx, batch_frame_names);
@@ -96,22 +172,44 @@ pub fn batch_channel_pipeline_infer(
                 {
                     break;
                 }
+                batch_idx += 1;
             }
         });
 
         // Stage 2: Model Inference thread
+        let infer_job = job.clone();
         let infer_handler = s.spawn(move || {
+            infer_job.set_stage(JobStage::Inferring);
             while let Ok((batch_idx, batch_images, batch_metas)) = load_rx.recv() {
+                infer_job.wait_while_paused();
+                if infer_job.is_cancelled() {
+                    tracing::info!("Job cancelled, stopping inference after batch {}.", batch_idx);
+                    break;
+                }
+
                 if verbose {
                     let batch_frame_names = get_batch_frame_names(&batch_metas);
                     tracing::debug!("[Inferring] batch {}: {:?}", batch_idx, batch_frame_names);
                 }
 
+                let pseudo_paths = vec!["".to_string(); batch_images.len()];
                 let batch_results: Vec<Option<ul::Results>> = if !infer_failed {
+                    let infer_start = Instant::now();
                     match model
                         .predict_batch(&batch_images, &pseudo_paths)
                     {
                         Ok(vec) => {
+                            if adaptive_batch {
+                                let target = sizer.record(batch_images.len(), infer_start.elapsed());
+                                if verbose {
+                                    tracing::debug!(
+                                        "[Adaptive] batch {}: target batch size {}",
+                                        batch_idx,
+                                        target
+                                    );
+                                }
+                                let _ = size_tx.send(target);
+                            }
                             // try to extract first element from each
                             vec.into_iter().map(|mut v| Some(v.remove(0))).collect()
                         }
@@ -123,6 +221,14 @@ pub fn batch_channel_pipeline_infer(
                             );
                             tracing::error!("> Error details: {:?}", e);
                             infer_failed = true;
+                            if adaptive_batch {
+                                let safe_size = sizer.reset(batch_size);
+                                tracing::info!(
+                                    "[Adaptive] resetting batch size to {} after fallback",
+                                    safe_size
+                                );
+                                let _ = size_tx.send(safe_size);
+                            }
                             batch_infer_fallback(model, &batch_images, &batch_metas, verbose)
                         }
                     }
@@ -137,11 +243,14 @@ pub fn batch_channel_pipeline_infer(
                     .zip(batch_results.into_iter())
                     .zip(batch_metas.into_iter())
                 {
-                    if let Some(r) = result {
-                        // Send inference results to next stage
-                        if infer_tx.send((batch_idx, image, r, meta)).is_err() {
-                            break;
+                    match result {
+                        Some(r) => {
+                            // Send inference results to next stage
+                            if infer_tx.send((batch_idx, image, r, meta)).is_err() {
+                                break;
+                            }
                         }
+                        None => infer_job.record_failed(),
                     }
                 }
 
@@ -149,15 +258,19 @@ pub fn batch_channel_pipeline_infer(
         });
 
         // Stage 3: Annotation thread
+        let annotate_job = job.clone();
         let annotate_handler = s.spawn(move || {
+            annotate_job.set_stage(JobStage::Annotating);
             while let Ok((batch_idx, image, results, meta)) = infer_rx.recv() {
+                let dims = image.dimensions();
+
                 // draw annotations
                 let annotated_img = if annotate {
                     if verbose {
                         tracing::debug!("[Annotating] batch {}: {}", batch_idx, &meta.frame_name());
                     }
 
-                    match annotate_image(&image, &results, annotate_cfg) {
+                    match annotate_image(&image, &results, annotate_cfg, None) {
                         Ok(img) => Some(img),
                         Err(e) => {
                             tracing::error!(
@@ -173,7 +286,7 @@ pub fn batch_channel_pipeline_infer(
                 };
                 // Send annotated image to saving stage
                 if annotate_tx
-                    .send((batch_idx, annotated_img, results, meta))
+                    .send((batch_idx, annotated_img, results, meta, dims))
                     .is_err()
                 {
                     break;
@@ -182,23 +295,39 @@ pub fn batch_channel_pipeline_infer(
         });
 
         // Stage 4: Saving thread
+        let save_job = job.clone();
         let save_handler = s.spawn(move || {
-            while let Ok((batch_idx, annotated_img, results, meta)) = annotate_rx.recv() {
-                if let Some(dir) = save_dir
-                    && let Some(annotated_img) = &annotated_img
-                {
-                    if verbose {
-                        tracing::debug!("[Saving] batch {}: {}", batch_idx, &meta.frame_name());
+            save_job.set_stage(JobStage::Saving);
+            while let Ok((batch_idx, annotated_img, results, meta, (width, height))) = annotate_rx.recv() {
+                if let Some(dir) = save_dir {
+                    let frame_stem = meta.frame_stem();
+
+                    if let Some(annotated_img) = &annotated_img {
+                        if verbose {
+                            tracing::debug!("[Saving] batch {}: {}", batch_idx, &meta.frame_name());
+                        }
+
+                        let save_path = dir.join(format!("{}.png", frame_stem));
+                        if annotated_img.save(&save_path).is_err() {
+                            tracing::error!(
+                                "Failed to save annotated image to {:?}. skipping.",
+                                save_path
+                            );
+                            continue;
+                        }
                     }
 
-                    let frame_stem = meta.frame_stem();
-                    let save_path = dir.join(format!("{}.png", frame_stem));
-                    if annotated_img.save(&save_path).is_err() {
-                        tracing::error!(
-                            "Failed to save annotated image to {:?}. skipping.",
-                            save_path
-                        );
-                        continue;
+                    if let Some(exporter) = exporter.as_mut()
+                        && let Err(e) = exporter.write_frame(dir, &frame_stem, &results, width, height)
+                    {
+                        tracing::error!("Failed to export detections for {}: {}", frame_stem, e);
+                    }
+
+                    // Checkpoint so an interrupted run can resume from here instead of redoing
+                    // already-processed frames, whether this run saves PNGs, exports detections,
+                    // or both.
+                    if let Err(e) = job::append_checkpoint(dir, &frame_stem) {
+                        tracing::warn!("Failed to write checkpoint for {}: {}", frame_stem, e);
                     }
                 }
                 // Send to collection stage
@@ -209,9 +338,17 @@ pub fn batch_channel_pipeline_infer(
                     break;
                 }
             }
+
+            if let Some(exporter) = exporter.as_mut()
+                && let Some(dir) = save_dir
+                && let Err(e) = exporter.finish(dir)
+            {
+                tracing::error!("Failed to finalize exported detections: {}", e);
+            }
         });
 
         // Stage 5: Collect results thread
+        let collect_job = job.clone();
         let collect_handler = s.spawn(move || {
             while let Ok((batch_idx, annotated_img, results, meta)) = save_rx.recv() {
                 // Update return results vector if provided
@@ -224,12 +361,16 @@ pub fn batch_channel_pipeline_infer(
                         result: results,
                         annotated: annotated_img,
                         meta: meta.clone(),
+                        track_ids: None,
                     });
                 }
 
+                collect_job.record_done();
+
                 // Update progress bar
                 pb.inc(1);
             }
+            collect_job.set_stage(JobStage::Done);
         });
 
         // Wait for pipeline threads to finish
@@ -246,5 +387,9 @@ pub fn batch_channel_pipeline_infer(
             save_dir.as_ref().unwrap()
         );
     }
+
+    if job.is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
     Ok(())
 }
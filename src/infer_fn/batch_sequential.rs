@@ -1,8 +1,11 @@
 use indicatif::{ProgressBar, ProgressFinish};
 use ultralytics_inference as ul;
 
+use image::GenericImageView;
+
 use crate::annotate::annotate_image;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::export::Exporter;
 use crate::predict::PredictArgs;
 use crate::progress_bar::progress_bar_style;
 use crate::source::{BatchSourceLoader, Source};
@@ -26,6 +29,7 @@ pub fn batch_sequential_infer(
     let batch_size = args.batch.unwrap_or(1);
     let verbose = args.verbose;
     let save = annotate && save_dir.is_some();
+    let job = args.job.clone();
 
     tracing::info!("Running sequential batch inference...");
     tracing::info!("[Source]: {:?}", source);
@@ -39,31 +43,47 @@ pub fn batch_sequential_infer(
         std::fs::create_dir_all(dir).expect("Failed to create save directory");
     }
 
+    let mut exporter = match (args.output_format, save_dir) {
+        (Some(format), Some(dir)) => Some(Exporter::new(format, dir)?),
+        _ => None,
+    };
+
     let loader = BatchSourceLoader::new(source, Some(batch_size))?;
     let total_batches = loader.len();
     let total_frames = loader.total_frames();
     tracing::info!("Total batches to process: {}", total_batches);
-    tracing::info!("Total frames to process: {}", total_frames);
+    match total_frames {
+        Some(n) => tracing::info!("Total frames to process: {}", n),
+        None => tracing::info!("Total frames to process: unknown (streaming source)"),
+    }
     tracing::info!("-----------------------------------------");
 
     // preserve space in return_results if provided
     if let Some(vec) = return_results.as_mut() {
         vec.clear();
-        vec.reserve(total_frames);
+        vec.reserve(total_frames.unwrap_or(0));
     }
 
     let pseudo_paths = vec!["".to_string(); batch_size];
 
-    // initialize progress bar
-    let pb = ProgressBar::new(total_frames as u64)
-        .with_style(progress_bar_style())
-        .with_message("Running inference")
-        .with_finish(ProgressFinish::WithMessage("Finished".into()));
+    // initialize progress bar (falls back to an unbounded spinner for a streaming source)
+    let pb = match total_frames {
+        Some(n) => ProgressBar::new(n as u64).with_style(progress_bar_style()),
+        None => ProgressBar::new_spinner(),
+    }
+    .with_message("Running inference")
+    .with_finish(ProgressFinish::WithMessage("Finished".into()));
 
     // record if batch inference has failed before
     let mut infer_failed = false;
 
     for (batch_idx, (batch_images, batch_metas)) in loader.enumerate() {
+        job.wait_while_paused();
+        if job.is_cancelled() {
+            tracing::info!("Job cancelled, stopping before batch {}.", batch_idx);
+            break;
+        }
+
         if verbose {
             let frame_names = get_batch_frame_names(&batch_metas);
             tracing::debug!("Processing batch {}: {:?}", batch_idx, frame_names);
@@ -111,7 +131,7 @@ pub fn batch_sequential_infer(
                     tracing::debug!("[Annotating]: {}", &meta.frame_name());
                 }
 
-                match annotate_image(&image, &results, annotate_cfg) {
+                match annotate_image(&image, &results, annotate_cfg, None) {
                     Ok(img) => Some(img),
                     Err(e) => {
                         tracing::error!(
@@ -127,21 +147,29 @@ pub fn batch_sequential_infer(
             };
 
             // Save annotated image if required
-            if let Some(dir) = save_dir
-                && let Some(annotated_img) = &annotated_img
-            {
-                if verbose {
-                    tracing::debug!("[Saving]: {}", &meta.frame_name());
+            if let Some(dir) = save_dir {
+                if let Some(annotated_img) = &annotated_img {
+                    if verbose {
+                        tracing::debug!("[Saving]: {}", &meta.frame_name());
+                    }
+
+                    let frame_stem = meta.frame_stem();
+                    let save_path = dir.join(format!("{}.png", frame_stem));
+                    if annotated_img.save(&save_path).is_err() {
+                        tracing::error!(
+                            "Failed to save annotated image to {:?}. skipping.",
+                            save_path
+                        );
+                        continue;
+                    }
                 }
 
-                let frame_stem = meta.frame_stem();
-                let save_path = dir.join(format!("{}.png", frame_stem));
-                if annotated_img.save(&save_path).is_err() {
-                    tracing::error!(
-                        "Failed to save annotated image to {:?}. skipping.",
-                        save_path
-                    );
-                    continue;
+                if let Some(exporter) = exporter.as_mut() {
+                    let frame_stem = meta.frame_stem();
+                    let (width, height) = image.dimensions();
+                    if let Err(e) = exporter.write_frame(dir, &frame_stem, &results, width, height) {
+                        tracing::error!("Failed to export detections for {}: {}", frame_stem, e);
+                    }
                 }
             }
 
@@ -155,6 +183,7 @@ pub fn batch_sequential_infer(
                     result: results,
                     annotated: annotated_img,
                     meta: meta.clone(),
+                    track_ids: None,
                 });
             }
 
@@ -163,6 +192,13 @@ pub fn batch_sequential_infer(
         }
     }
 
+    if let Some(exporter) = exporter.as_mut()
+        && let Some(dir) = save_dir
+        && let Err(e) = exporter.finish(dir)
+    {
+        tracing::error!("Failed to finalize exported detections: {}", e);
+    }
+
     if save {
         tracing::info!(
             "Results saved to directory: {:?}",
@@ -170,5 +206,8 @@ pub fn batch_sequential_infer(
         );
     }
 
+    if job.is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
     Ok(())
 }
@@ -1,8 +1,81 @@
 use image::DynamicImage;
+use std::collections::VecDeque;
+use std::time::Duration;
 use ultralytics_inference as ul;
 
 use crate::source::SourceMeta;
 
+/// Number of recent batches' throughput samples kept when hill-climbing the batch size.
+const THROUGHPUT_WINDOW: usize = 5;
+
+/// Hill-climbs the effective batch size toward whichever size maximizes recent frames/sec,
+/// within `[min, max]`. Fed one latency sample per successfully inferred batch; grows the size
+/// while throughput keeps improving and reverses direction once it stops, so it settles near
+/// whatever the accelerator and pipeline can sustain.
+pub struct AdaptiveBatchSizer {
+    min: usize,
+    max: usize,
+    current: usize,
+    growing: bool,
+    best_fps: f64,
+    recent_fps: VecDeque<f64>,
+}
+
+impl AdaptiveBatchSizer {
+    pub fn new(initial: usize, min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+        Self {
+            min,
+            max,
+            current: initial.clamp(min, max),
+            growing: true,
+            best_fps: 0.0,
+            recent_fps: VecDeque::with_capacity(THROUGHPUT_WINDOW),
+        }
+    }
+
+    /// Record one batch's inference latency and hill-climb toward a better batch size.
+    /// Returns the newly chosen target size.
+    pub fn record(&mut self, batch_len: usize, elapsed: Duration) -> usize {
+        if batch_len == 0 || elapsed.is_zero() {
+            return self.current;
+        }
+
+        let fps = batch_len as f64 / elapsed.as_secs_f64();
+        if self.recent_fps.len() == THROUGHPUT_WINDOW {
+            self.recent_fps.pop_front();
+        }
+        self.recent_fps.push_back(fps);
+        let avg_fps = self.recent_fps.iter().sum::<f64>() / self.recent_fps.len() as f64;
+
+        if avg_fps >= self.best_fps {
+            self.best_fps = avg_fps;
+        } else {
+            // Throughput regressed since the last step: reverse direction and try the other way.
+            self.growing = !self.growing;
+        }
+
+        self.current = if self.growing {
+            (self.current + 1).min(self.max)
+        } else {
+            self.current.saturating_sub(1).max(self.min)
+        };
+
+        self.current
+    }
+
+    /// Reset to a safe size after `batch_infer_fallback` is triggered, discarding throughput
+    /// history so the climb restarts from scratch.
+    pub fn reset(&mut self, safe_size: usize) -> usize {
+        self.current = safe_size.clamp(self.min, self.max);
+        self.growing = true;
+        self.best_fps = 0.0;
+        self.recent_fps.clear();
+        self.current
+    }
+}
+
 /// Get frame names for a batch of source metas
 pub fn get_batch_frame_names(batch_metas: &Vec<SourceMeta>) -> Vec<String> {
     let mut frame_names: Vec<String> = Vec::with_capacity(batch_metas.len());
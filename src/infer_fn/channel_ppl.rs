@@ -1,14 +1,16 @@
-use image::DynamicImage;
+use image::{DynamicImage, GenericImageView};
 use indicatif::{ProgressBar, ProgressFinish};
-use std::sync::mpsc;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, mpsc};
 use std::thread;
 use ultralytics_inference as ul;
 
 use crate::annotate::annotate_image;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::export::Exporter;
 use crate::predict::PredictArgs;
 use crate::progress_bar::progress_bar_style;
-use crate::source::{SourceLoader, SourceMeta};
+use crate::source::{SourceLoader, SourceMeta, VideoFrameWriter};
 
 use super::InferResult;
 
@@ -26,8 +28,11 @@ pub fn channel_pipeline_infer(
     let annotate_cfg = &args.annotate_cfg;
     let save_dir = &args.save_dir;
     let channel_capacity = args.channel_capacity.unwrap_or(8);
+    let infer_workers = args.infer_workers.max(1);
+    let save_as_video = args.save_as_video;
     let verbose = args.verbose;
     let save = annotate && save_dir.is_some();
+    let job = args.job.clone();
 
     tracing::info!("Running channel-based pipeline inference...");
     tracing::info!("[Source]: {:?}", args.source);
@@ -40,23 +45,38 @@ pub fn channel_pipeline_infer(
         }
         std::fs::create_dir_all(dir).expect("Failed to create save directory");
     }
+
+    let mut exporter = match (args.output_format, save_dir) {
+        (Some(format), Some(dir)) => Some(Exporter::new(format, dir)?),
+        _ => None,
+    };
+
     // Initialize source loader
-    let loader = SourceLoader::new(source);
-    let total_frames = loader.len();
-    tracing::info!("Total frames to process: {}", total_frames);
+    let loader = SourceLoader::new(source, args.cache_dir.clone(), args.imgsz)?;
+    let total_frames = loader.total_frames();
+    let fps = loader.fps();
+    match total_frames {
+        Some(n) => tracing::info!("Total frames to process: {}", n),
+        None => tracing::info!("Total frames to process: unknown (streaming source)"),
+    }
     tracing::info!("-----------------------------------------");
 
     // preserve space in return_results if provided
     if let Some(vec) = return_results.as_mut() {
         vec.clear();
-        vec.reserve(total_frames);
+        vec.reserve(total_frames.unwrap_or(0));
     }
 
-    // Define data types for each pipeline stage
+    // Define data types for each pipeline stage.
+    //
+    // Every stage past loading carries `SourceMeta` alongside an `Option` payload: `None` means
+    // that frame failed somewhere upstream (a prediction or annotation error), but its slot still
+    // has to travel all the way to the collect stage so `next_expected` can advance past it —
+    // otherwise a single failed frame would wedge the reorder buffer forever.
     type LoadStage = (DynamicImage, SourceMeta);
-    type InferStage = (DynamicImage, ul::Results, SourceMeta);
-    type AnnotateStage = (Option<DynamicImage>, ul::Results, SourceMeta);
-    type SaveStage = (Option<DynamicImage>, ul::Results, SourceMeta);
+    type InferStage = (SourceMeta, Option<(DynamicImage, ul::Results)>);
+    type AnnotateStage = (SourceMeta, Option<(Option<DynamicImage>, ul::Results, (u32, u32))>);
+    type SaveStage = (SourceMeta, Option<(Option<DynamicImage>, ul::Results, (u32, u32))>);
 
     // Create channels for pipeline stages with bounded capacity
     let (load_tx, load_rx) = mpsc::sync_channel::<LoadStage>(channel_capacity);
@@ -64,17 +84,52 @@ pub fn channel_pipeline_infer(
     let (annotate_tx, annotate_rx) = mpsc::sync_channel::<AnnotateStage>(channel_capacity);
     let (save_tx, save_rx) = mpsc::sync_channel::<SaveStage>(channel_capacity);
 
-    // initialize progress bar
-    let pb = ProgressBar::new(total_frames as u64)
-        .with_style(progress_bar_style())
-        .with_message("Running inference")
-        .with_finish(ProgressFinish::WithMessage("Finished".into()));
+    // initialize progress bar (falls back to an unbounded spinner for a streaming source)
+    let pb = match total_frames {
+        Some(n) => ProgressBar::new(n as u64).with_style(progress_bar_style()),
+        None => ProgressBar::new_spinner(),
+    }
+    .with_message("Running inference")
+    .with_finish(ProgressFinish::WithMessage("Finished".into()));
+
+    // `model` handles one worker; load an independent session per additional worker so each can
+    // run `predict_image` concurrently without contending on a shared `&mut`.
+    let mut extra_models = Vec::with_capacity(infer_workers.saturating_sub(1));
+    for _ in 1..infer_workers {
+        let config: ul::InferenceConfig = args.try_into()?;
+        let extra_model = ul::YOLOModel::load_with_config(&args.model, config)
+            .map_err(|e| AppError::ModelLoad(e.to_string()))?;
+        extra_models.push(extra_model);
+    }
+    let mut worker_models: Vec<&mut ul::YOLOModel> = Vec::with_capacity(infer_workers);
+    worker_models.push(model);
+    worker_models.extend(extra_models.iter_mut());
+
+    // Shared by all inference workers: each pulls the next loaded frame off the same receiver,
+    // so frames are handed out in order but finish out of order once more than one worker exists.
+    let load_rx = Mutex::new(load_rx);
 
     // Use scoped threads to allow borrowing model
     thread::scope(|s| {
         // Stage 1: Image Loading thread
+        let load_job = job.clone();
         let load_handler = s.spawn(move || {
+            // Sequence number of the next frame handed to inference, see below.
+            let mut load_seq = 0usize;
             for (image, meta) in loader {
+                load_job.wait_while_paused();
+                if load_job.is_cancelled() {
+                    tracing::info!("Job cancelled, stopping loading after frame {}.", meta.frame_idx);
+                    break;
+                }
+
+                // Number frames by load order rather than by position in the source: the loader
+                // skips a frame it can't decode, and a gap in `frame_idx` would leave the collect
+                // stage's reorder buffer waiting for that frame forever.
+                let mut meta = meta;
+                meta.frame_idx = load_seq;
+                load_seq += 1;
+
                 if verbose {
                     tracing::debug!("[Loading]: {}", &meta.frame_name());
                 }
@@ -86,51 +141,80 @@ pub fn channel_pipeline_infer(
             }
         });
 
-        // Stage 2: Model inference thread
-        let infer_handler = s.spawn(move || {
-            while let Ok((image, meta)) = load_rx.recv() {
-                if verbose {
-                    tracing::debug!("[Inferring]: {}", &meta.frame_name());
-                }
-                let results_vec = match model.predict_image(&image, "".to_string()) {
-                    Ok(res) => res,
-                    Err(e) => {
-                        tracing::error!(
-                            "Prediction failed for image: {:?}, skipping. Error: {}",
-                            &meta.source_path,
-                            e
-                        );
-                        continue;
+        // Stage 2: Model inference threads (one per worker, sharing `load_rx`)
+        let load_rx = &load_rx;
+        let mut infer_handlers = Vec::with_capacity(infer_workers);
+        for worker_model in worker_models {
+            let infer_tx = infer_tx.clone();
+            let infer_job = job.clone();
+            let handle = s.spawn(move || {
+                loop {
+                    infer_job.wait_while_paused();
+                    if infer_job.is_cancelled() {
+                        tracing::info!("Job cancelled, stopping inference.");
+                        break;
                     }
-                };
-                // One image at a time
-                let results = match results_vec.into_iter().next() {
-                    Some(r) => r,
-                    None => {
-                        tracing::error!(
-                            "No results returned for image: {:?}, skipping.",
-                            &meta.source_path
-                        );
-                        continue;
+
+                    let received = load_rx.lock().expect("load_rx mutex poisoned").recv();
+                    let Ok((image, meta)) = received else {
+                        break;
+                    };
+
+                    if verbose {
+                        tracing::debug!("[Inferring]: {}", &meta.frame_name());
+                    }
+                    let results_vec = match worker_model.predict_image(&image, "".to_string()) {
+                        Ok(res) => res,
+                        Err(e) => {
+                            tracing::error!(
+                                "Prediction failed for image: {:?}, skipping. Error: {}",
+                                &meta.source_path,
+                                e
+                            );
+                            let _ = infer_tx.send((meta, None));
+                            continue;
+                        }
+                    };
+                    // One image at a time
+                    let results = match results_vec.into_iter().next() {
+                        Some(r) => r,
+                        None => {
+                            tracing::error!(
+                                "No results returned for image: {:?}, skipping.",
+                                &meta.source_path
+                            );
+                            let _ = infer_tx.send((meta, None));
+                            continue;
+                        }
+                    };
+                    // Send inference results to annotation stage
+                    if infer_tx.send((meta, Some((image, results)))).is_err() {
+                        break;
                     }
-                };
-                // Send inference results to annotation stage
-                if infer_tx.send((image, results, meta)).is_err() {
-                    break;
                 }
-            }
-        });
+            });
+            infer_handlers.push(handle);
+        }
+        drop(infer_tx);
 
         // Stage 3: Draw annotation thread
         let annotate_handler = s.spawn(move || {
-            while let Ok((image, results, meta)) = infer_rx.recv() {
+            while let Ok((meta, payload)) = infer_rx.recv() {
+                let Some((image, results)) = payload else {
+                    // Inference already failed upstream; forward the miss so the collect stage
+                    // can still advance past this frame_idx.
+                    let _ = annotate_tx.send((meta, None));
+                    continue;
+                };
+                let dims = image.dimensions();
+
                 // draw annotations
                 let annotated_img = if annotate {
                     if verbose {
                         tracing::debug!("[Annotating]: {}", &meta.frame_name());
                     }
 
-                    match annotate_image(&image, &results, annotate_cfg) {
+                    match annotate_image(&image, &results, annotate_cfg, None) {
                         Ok(img) => Some(img),
                         Err(e) => {
                             tracing::error!(
@@ -138,6 +222,7 @@ pub fn channel_pipeline_infer(
                                 &meta.source_path,
                                 e
                             );
+                            let _ = annotate_tx.send((meta, None));
                             continue;
                         }
                     }
@@ -145,63 +230,155 @@ pub fn channel_pipeline_infer(
                     None
                 };
                 // Send annotated image to saving stage
-                if annotate_tx.send((annotated_img, results, meta)).is_err() {
+                if annotate_tx.send((meta, Some((annotated_img, results, dims)))).is_err() {
                     break;
                 }
             }
         });
 
         // Stage 4: Saving thread
+        //
+        // Per-frame PNG saving and structured export don't care about arrival order (each frame
+        // is keyed by its own name), so they still happen here even when inference workers
+        // deliver frames out of order. Re-encoding into a single output video does care about
+        // order, so that happens downstream in the collect stage once frames are reassembled.
         let save_handler = s.spawn(move || {
-            while let Ok((annotated_img, results, meta)) = annotate_rx.recv() {
-                if let Some(dir) = save_dir
-                    && let Some(annotated_img) = &annotated_img
-                {
-                    if verbose {
-                        tracing::debug!("[Saving]: {}", &meta.frame_name());
+            while let Ok((meta, payload)) = annotate_rx.recv() {
+                let Some((annotated_img, results, dims)) = payload else {
+                    // Upstream already failed this frame; forward the miss unchanged.
+                    let _ = save_tx.send((meta, None));
+                    continue;
+                };
+                if let Some(dir) = save_dir {
+                    if !save_as_video
+                        && let Some(annotated_img) = &annotated_img
+                    {
+                        if verbose {
+                            tracing::debug!("[Saving]: {}", &meta.frame_name());
+                        }
+
+                        let frame_stem = meta.frame_stem();
+                        let save_path = dir.join(format!("{}.png", frame_stem));
+                        if annotated_img.save(&save_path).is_err() {
+                            tracing::error!(
+                                "Failed to save annotated image to {:?}. skipping.",
+                                save_path
+                            );
+                            // Forward the miss so the collect stage can still advance past it.
+                            let _ = save_tx.send((meta, None));
+                            continue;
+                        }
                     }
 
-                    let frame_stem = meta.frame_stem();
-                    let save_path = dir.join(format!("{}.png", frame_stem));
-                    if annotated_img.save(&save_path).is_err() {
-                        tracing::error!(
-                            "Failed to save annotated image to {:?}. skipping.",
-                            save_path
-                        );
-                        continue;
+                    if let Some(exporter) = exporter.as_mut() {
+                        let frame_stem = meta.frame_stem();
+                        let (width, height) = dims;
+                        if let Err(e) = exporter.write_frame(dir, &frame_stem, &results, width, height) {
+                            tracing::error!("Failed to export detections for {}: {}", frame_stem, e);
+                        }
                     }
                 }
 
-                if save_tx.send((annotated_img, results, meta)).is_err() {
+                if save_tx.send((meta, Some((annotated_img, results, dims)))).is_err() {
                     break;
                 }
             }
+
+            if let Some(exporter) = exporter.as_mut()
+                && let Some(dir) = save_dir
+                && let Err(e) = exporter.finish(dir)
+            {
+                tracing::error!("Failed to finalize exported detections: {}", e);
+            }
         });
 
         // Stage 5: Collect results thread
+        //
+        // Multiple inference workers finish frames out of order, so results arrive here out of
+        // order too. Reassemble them: buffer each arrival by `frame_idx` (`None` for a frame that
+        // failed upstream), then drain consecutive entries starting at `next_expected` into
+        // `return_results` and/or the output video writer, both of which need source order. A
+        // failed frame's slot still arrives and still gets drained, so `next_expected` advances
+        // past it instead of stalling forever. The buffer itself has no size limit: this stage
+        // keeps receiving while it waits for `next_expected`, so every frame the other workers
+        // finish while one frame lags stays buffered until the lagging frame arrives.
         let collect_handler = s.spawn(move || {
-            while let Ok((annotated_img, results, meta)) = save_rx.recv() {
-                // Update return results vector if provided
-                if let Some(vec) = return_results {
-                    if verbose {
-                        tracing::debug!("[Collecting] result for: {}", &meta.frame_name());
-                    }
+            let mut pending: BTreeMap<usize, Option<InferResult>> = BTreeMap::new();
+            let mut next_expected = 0usize;
+            let mut video_writer: Option<VideoFrameWriter> = None;
 
-                    vec.push(InferResult {
-                        result: results,
-                        annotated: annotated_img,
-                        meta,
-                    });
+            while let Ok((meta, payload)) = save_rx.recv() {
+                if verbose {
+                    tracing::debug!("[Collecting] result for: {}", &meta.frame_name());
                 }
 
-                // Update progress bar
                 pb.inc(1);
+
+                if return_results.is_none() && !save_as_video {
+                    continue;
+                }
+
+                let entry = payload.map(|(annotated_img, results, _dims)| InferResult {
+                    result: results,
+                    annotated: annotated_img,
+                    meta: meta.clone(),
+                    track_ids: None,
+                });
+                pending.insert(meta.frame_idx, entry);
+
+                while let Some(entry) = pending.remove(&next_expected) {
+                    next_expected += 1;
+
+                    let Some(entry) = entry else {
+                        // This frame failed somewhere upstream; nothing to write, just move on.
+                        continue;
+                    };
+
+                    if save_as_video
+                        && let Some(dir) = save_dir
+                        && let Some(annotated_img) = entry.annotated.as_ref()
+                    {
+                        let writer = match video_writer.as_mut() {
+                            Some(w) => Some(w),
+                            None => {
+                                let (width, height) = annotated_img.dimensions();
+                                match VideoFrameWriter::create(&dir.join("output.mp4"), width, height, fps)
+                                {
+                                    Ok(w) => Some(video_writer.insert(w)),
+                                    Err(e) => {
+                                        tracing::error!(
+                                            "Failed to open output video, skipping frame: {e}"
+                                        );
+                                        None
+                                    }
+                                }
+                            }
+                        };
+                        if let Some(writer) = writer
+                            && let Err(e) = writer.write_frame(annotated_img)
+                        {
+                            tracing::error!("Failed to encode frame into output video: {e}");
+                        }
+                    }
+
+                    if let Some(vec) = return_results.as_mut() {
+                        vec.push(entry);
+                    }
+                }
+            }
+
+            if let Some(writer) = video_writer
+                && let Err(e) = writer.finish()
+            {
+                tracing::error!("Failed to finalize output video: {e}");
             }
         });
 
         // Wait for pipeline threads to finish
         load_handler.join().expect("Loading thread panicked");
-        infer_handler.join().expect("Inference thread panicked");
+        for handle in infer_handlers {
+            handle.join().expect("Inference thread panicked");
+        }
         annotate_handler.join().expect("Annotation thread panicked");
         save_handler.join().expect("Saving thread panicked");
         collect_handler.join().expect("Collect thread panicked");
@@ -213,5 +390,9 @@ pub fn channel_pipeline_infer(
             save_dir.as_ref().unwrap()
         );
     }
+
+    if job.is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
     Ok(())
 }
@@ -1,11 +1,14 @@
+use image::GenericImageView;
 use indicatif::{ProgressFinish, ProgressIterator};
 use ultralytics_inference as ul;
 
 use crate::annotate::annotate_image;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::export::Exporter;
 use crate::predict::PredictArgs;
 use crate::progress_bar::progress_bar_style;
-use crate::source::SourceLoader;
+use crate::source::{SourceLoader, VideoFrameWriter};
+use crate::track::{Detection, Tracker, TrackerConfig};
 
 use super::InferResult;
 
@@ -22,8 +25,10 @@ pub fn sequential_infer(
     let annotate = args.annotate;
     let annotate_cfg = &args.annotate_cfg;
     let save_dir = &args.save_dir;
+    let save_as_video = args.save_as_video;
     let verbose = args.verbose;
     let save = annotate && save_dir.is_some();
+    let job = args.job.clone();
 
     tracing::info!("Running naive sequential inference...");
     tracing::info!("[Source]: {:?}", args.source);
@@ -36,11 +41,28 @@ pub fn sequential_infer(
         std::fs::create_dir_all(dir).expect("Failed to create save directory");
     }
 
-    let loader = SourceLoader::new(source)?;
+    let mut exporter = match (args.output_format, save_dir) {
+        (Some(format), Some(dir)) => Some(Exporter::new(format, dir)?),
+        _ => None,
+    };
+
+    let loader = SourceLoader::new(source, args.cache_dir.clone(), args.imgsz)?;
     let total_frames = loader.len();
+    let fps = loader.fps();
     tracing::info!("Total frames to process: {}", total_frames);
     tracing::info!("-----------------------------------------");
 
+    // Lazily opened once the first annotated frame's dimensions are known.
+    let mut video_writer: Option<VideoFrameWriter> = None;
+
+    let mut tracker = args.tracking.then(|| {
+        Tracker::new(TrackerConfig {
+            max_age: args.track_max_age,
+            iou_threshold: args.track_iou_threshold,
+            ..Default::default()
+        })
+    });
+
     // preserve space in return_results if provided
     if let Some(vec) = return_results.as_mut() {
         vec.clear();
@@ -53,6 +75,12 @@ pub fn sequential_infer(
         .with_message("Running inference")
         .with_finish(ProgressFinish::WithMessage("Finished".into()))
     {
+        job.wait_while_paused();
+        if job.is_cancelled() {
+            tracing::info!("Job cancelled, stopping after frame {}.", idx);
+            break;
+        }
+
         if verbose {
             match &meta.source_path {
                 Some(p) => {
@@ -86,9 +114,27 @@ pub fn sequential_infer(
             }
         };
 
+        // Assign persistent track IDs, if enabled
+        let track_ids: Option<Vec<u64>> = tracker.as_mut().map(|tracker| {
+            let detections: Vec<Detection> = match results.boxes.as_ref() {
+                Some(boxes) => {
+                    let xyxy = boxes.xyxy();
+                    let conf = boxes.conf();
+                    (0..boxes.len())
+                        .map(|i| Detection {
+                            xyxy: [xyxy[[i, 0]], xyxy[[i, 1]], xyxy[[i, 2]], xyxy[[i, 3]]],
+                            conf: conf[i],
+                        })
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+            tracker.update(&detections)
+        });
+
         // draw annotations
         let annotated_img = if annotate {
-            match annotate_image(&image, &results, annotate_cfg) {
+            match annotate_image(&image, &results, annotate_cfg, track_ids.as_deref()) {
                 Ok(img) => Some(img),
                 Err(e) => {
                     tracing::error!(
@@ -104,17 +150,46 @@ pub fn sequential_infer(
         };
 
         // Save results if save_dir is specified
-        if let Some(dir) = save_dir
-            && let Some(annotated_img) = &annotated_img
-        {
-            let frame_stem = meta.frame_stem();
-            let save_path = dir.join(format!("{}.png", frame_stem));
-            if annotated_img.save(&save_path).is_err() {
-                tracing::error!(
-                    "Failed to save annotated image to {:?}. skipping.",
-                    save_path
-                );
-                continue;
+        if let Some(dir) = save_dir {
+            if let Some(annotated_img) = &annotated_img {
+                if save_as_video {
+                    let writer = match video_writer.as_mut() {
+                        Some(w) => w,
+                        None => {
+                            let (width, height) = annotated_img.dimensions();
+                            match VideoFrameWriter::create(&dir.join("output.mp4"), width, height, fps)
+                            {
+                                Ok(w) => video_writer.insert(w),
+                                Err(e) => {
+                                    tracing::error!("Failed to open output video, skipping frame: {e}");
+                                    continue;
+                                }
+                            }
+                        }
+                    };
+                    if let Err(e) = writer.write_frame(annotated_img) {
+                        tracing::error!("Failed to encode frame into output video: {e}");
+                        continue;
+                    }
+                } else {
+                    let frame_stem = meta.frame_stem();
+                    let save_path = dir.join(format!("{}.png", frame_stem));
+                    if annotated_img.save(&save_path).is_err() {
+                        tracing::error!(
+                            "Failed to save annotated image to {:?}. skipping.",
+                            save_path
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            if let Some(exporter) = exporter.as_mut() {
+                let frame_stem = meta.frame_stem();
+                let (width, height) = image.dimensions();
+                if let Err(e) = exporter.write_frame(dir, &frame_stem, &results, width, height) {
+                    tracing::error!("Failed to export detections for {}: {}", frame_stem, e);
+                }
             }
         }
 
@@ -124,15 +199,33 @@ pub fn sequential_infer(
                 result: results,
                 annotated: annotated_img,
                 meta,
+                track_ids,
             });
         }
     }
 
+    if let Some(writer) = video_writer
+        && let Err(e) = writer.finish()
+    {
+        tracing::error!("Failed to finalize output video: {e}");
+    }
+
+    if let Some(exporter) = exporter.as_mut()
+        && let Some(dir) = save_dir
+        && let Err(e) = exporter.finish(dir)
+    {
+        tracing::error!("Failed to finalize exported detections: {}", e);
+    }
+
     if save {
         tracing::info!(
             "Results saved to directory: {:?}",
             save_dir.as_ref().unwrap()
         );
     }
+
+    if job.is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
     Ok(())
 }
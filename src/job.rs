@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{AppError, Result};
+
+/// How long a paused job's stages sleep between checks of [`JobHandle::is_paused`]/
+/// [`JobHandle::is_cancelled`].
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Name of the checkpoint sidecar file written to `save_dir`, listing the frame stems already
+/// completed so an interrupted run can resume without redoing finished work.
+pub const CHECKPOINT_FILE: &str = ".yolo_checkpoint";
+
+/// Which stage of the pipeline a job is currently in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JobStage {
+    #[default]
+    Loading,
+    Inferring,
+    Annotating,
+    Saving,
+    Done,
+}
+
+impl fmt::Display for JobStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            JobStage::Loading => "loading",
+            JobStage::Inferring => "inferring",
+            JobStage::Annotating => "annotating",
+            JobStage::Saving => "saving",
+            JobStage::Done => "done",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Handle to a running (or not-yet-started) prediction job.
+///
+/// Cloning a `JobHandle` is cheap and shares the same cancellation flag and counters, so a
+/// caller can keep one copy to call [`JobHandle::cancel`] from another thread while passing a
+/// clone into `PredictArgs::job`, and poll [`JobHandle::frames_done`]/[`JobHandle::frames_failed`]
+/// /[`JobHandle::stage`] at any time to render its own progress UI instead of relying on the
+/// `indicatif` bar. The frame counters and stage are only updated by
+/// `batch_channel_pipeline_infer`; the other inference functions leave them at their initial
+/// values.
+#[derive(Debug, Clone, Default)]
+pub struct JobHandle {
+    cancelled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    frames_done: Arc<AtomicUsize>,
+    frames_failed: Arc<AtomicUsize>,
+    stage: Arc<Mutex<JobStage>>,
+}
+
+impl JobHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the job to stop gracefully. The pipeline finishes the in-flight batch and joins
+    /// its threads cleanly rather than stopping mid-batch.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Suspend the job before its next cooperative checkpoint. Already in-flight frames finish
+    /// normally; the pipeline simply stops pulling new work until [`Self::resume`] is called.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Block the calling stage while the job is paused, waking up early if it's cancelled
+    /// instead. Pipeline stages call this at their cooperative checkpoints, alongside the
+    /// existing [`Self::is_cancelled`] check.
+    pub(crate) fn wait_while_paused(&self) {
+        while self.is_paused() && !self.is_cancelled() {
+            std::thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+    }
+
+    /// Route `Ctrl-C`/`SIGINT` to [`Self::cancel`], so an interrupted run stops its pipeline
+    /// threads cleanly (drains in-flight frames, flushes open output sinks, joins) instead of
+    /// the process dying mid-write. Only one handler can be installed per process; call this at
+    /// most once, typically right before starting the job.
+    pub fn install_ctrlc_handler(&self) -> Result<()> {
+        let job = self.clone();
+        ctrlc::set_handler(move || {
+            tracing::info!("Received interrupt signal, cancelling job...");
+            job.cancel();
+        })
+        .map_err(|e| AppError::Config(format!("Failed to install Ctrl-C handler: {e}")))
+    }
+
+    pub fn frames_done(&self) -> usize {
+        self.frames_done.load(Ordering::Relaxed)
+    }
+
+    pub fn frames_failed(&self) -> usize {
+        self.frames_failed.load(Ordering::Relaxed)
+    }
+
+    pub fn stage(&self) -> JobStage {
+        *self.stage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn set_stage(&self, stage: JobStage) {
+        *self.stage.lock().unwrap_or_else(|e| e.into_inner()) = stage;
+    }
+
+    pub(crate) fn record_done(&self) {
+        self.frames_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_failed(&self) {
+        self.frames_failed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Load the set of frame stems already completed by a previous run, from the checkpoint sidecar
+/// file in `save_dir`, if any.
+pub fn load_checkpoint(save_dir: &Path) -> HashSet<String> {
+    match std::fs::read_to_string(save_dir.join(CHECKPOINT_FILE)) {
+        Ok(contents) => contents.lines().map(str::to_string).collect(),
+        Err(_) => HashSet::new(),
+    }
+}
+
+/// Append a newly completed frame stem to the checkpoint file in `save_dir`.
+pub fn append_checkpoint(save_dir: &Path, frame_stem: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(save_dir.join(CHECKPOINT_FILE))?;
+    writeln!(file, "{frame_stem}")
+}
@@ -1,21 +1,31 @@
 mod annotate;
 mod error;
+mod export;
 mod ffi;
+mod fuzzy;
 mod infer_fn;
+mod job;
 mod logging;
 mod predict;
 mod progress_bar;
 mod source;
 mod toml_utils;
+mod track;
 
-pub use annotate::{AnnotateConfigs, annotate_image};
+pub use annotate::{
+    AnnotateConfigs, KPT_COLOR_INDICES, LIMB_COLOR_INDICES, SKELETON, annotate_image,
+    draw_pose_skeleton,
+};
 pub use error::{AppError, Result};
+pub use export::OutputFormat;
 pub use infer_fn::{InferFn, auto_infer};
+pub use job::{JobHandle, JobStage};
 pub use logging::init_logger;
 pub use progress_bar::progress_bar_style;
 pub use source::{BatchSourceLoader, Source, SourceLoader, SourceMeta, collect_images_from_dir,
                  is_image_file};
-pub use toml_utils::parse_toml;
+pub use toml_utils::{CONFIG_FILE_NAME, discover_and_parse, parse_toml, parse_toml_layered};
+pub use track::{Detection, Tracker, TrackerConfig};
 
 // Core inference function
 pub use predict::{PredictArgs, run_online_prediction, run_prediction};
@@ -5,7 +5,9 @@ use ultralytics_inference as ul;
 
 use crate::annotate::AnnotateConfigs;
 use crate::error::{AppError, Result};
+use crate::export::{OutputFormat, deserialize_output_format};
 use crate::infer_fn::{InferFn, InferResult, auto_infer, deserialize_infer_fn};
+use crate::job::JobHandle;
 use crate::source::{Source, deserialize_source};
 
 #[derive(Debug, Clone, Deserialize)]
@@ -36,16 +38,57 @@ pub struct PredictArgs {
     /// Batch size for inference
     pub batch: Option<usize>,
 
+    /// Whether to adapt the effective batch size at runtime to maximize frames/sec, instead of
+    /// keeping `batch` fixed. Only `InferFn::BatchChannelPipeline` adapts, and only for
+    /// `Source::Video`/`Source::Stream` inputs; image-backed sources are chunked into
+    /// `batch`-sized batches up front and keep that size.
+    pub adaptive_batch: bool,
+
+    /// Lower bound for the adaptive batch size. Ignored unless `adaptive_batch` is set.
+    pub batch_min: Option<usize>,
+
+    /// Upper bound for the adaptive batch size. Ignored unless `adaptive_batch` is set.
+    pub batch_max: Option<usize>,
+
     /// Device to use (cpu, cuda:0, mps, coreml, directml:0, openvino, tensorrt:0, etc.)
     pub device: Option<String>,
 
     /// Directory to save results
     pub save_dir: Option<PathBuf>,
 
+    /// Mux annotated frames into a single `output.mp4` under `save_dir` instead of writing one
+    /// PNG per frame. Most useful for `Source::Video`/`Source::Stream` inputs. Applied by
+    /// `InferFn::Sequential` and `InferFn::ChannelPipeline`; the batch inference functions ignore
+    /// it and always write PNGs.
+    pub save_as_video: bool,
+
+    /// Structured detection export format (`yolo`, `coco`, `csv`, `jsonl`), written to
+    /// `save_dir` alongside annotated images
+    #[serde(default, deserialize_with = "deserialize_output_format")]
+    pub output_format: Option<OutputFormat>,
+
+    /// Directory to cache decoded-and-resized images in, keyed by source path/mtime/`imgsz`.
+    /// Only used by `SourceLoader` (image/directory sources); speeds up repeated runs against an
+    /// unchanged source. `None` disables caching.
+    pub cache_dir: Option<PathBuf>,
+
     /// Inference function to use
     #[serde(default, deserialize_with = "deserialize_infer_fn")]
     pub infer_fn: InferFn,
 
+    /// Assign persistent track IDs to detections across frames (SORT/ByteTrack-style), so
+    /// `annotate_cfg` can render `ID:label conf` instead of just `label conf`. Only meaningful
+    /// for video/directory sources; a single still image has nothing to track across. Only
+    /// `InferFn::Sequential` runs the tracker; the other inference functions ignore this flag.
+    pub tracking: bool,
+
+    /// Frames a track may go unmatched before it's dropped. Ignored unless `tracking` is set.
+    pub track_max_age: usize,
+
+    /// Minimum IoU for a track/detection pair to be considered a match. Ignored unless
+    /// `tracking` is set.
+    pub track_iou_threshold: f32,
+
     /// Whether to generate annotations
     pub annotate: bool,
 
@@ -55,11 +98,23 @@ pub struct PredictArgs {
     /// Multi-thread channel capacity
     pub channel_capacity: Option<usize>,
 
+    /// Number of concurrent inference workers in `channel_pipeline_infer`, each holding its own
+    /// model session. Values greater than `1` scale inference across cores; results stay in
+    /// source order regardless, via the collect stage's reorder buffer.
+    pub infer_workers: usize,
+
     /// Whether to store and return inference results
     pub return_result: bool,
 
     /// Show verbose output
     pub verbose: bool,
+
+    /// Handle for cancelling this job and polling its progress from another thread.
+    ///
+    /// Not configurable from TOML; clone the handle before passing `args` in so you keep a copy
+    /// to call [`JobHandle::cancel`] on.
+    #[serde(skip)]
+    pub job: JobHandle,
 }
 
 impl Default for PredictArgs {
@@ -73,14 +128,25 @@ impl Default for PredictArgs {
             imgsz: None,
             half: false,
             batch: Some(4),
+            adaptive_batch: false,
+            batch_min: None,
+            batch_max: None,
             device: None,
             save_dir: None,
+            save_as_video: false,
+            output_format: None,
+            cache_dir: None,
             infer_fn: Default::default(),
+            tracking: false,
+            track_max_age: 30,
+            track_iou_threshold: 0.3,
             annotate: false,
             annotate_cfg: Default::default(),
             channel_capacity: Some(8),
+            infer_workers: 1,
             return_result: false,
             verbose: false,
+            job: JobHandle::default(),
         }
     }
 }
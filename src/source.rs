@@ -2,10 +2,14 @@
 mod batch_loader;
 mod loader;
 mod source_utils;
+mod video;
+mod video_writer;
 
 pub use batch_loader::BatchSourceLoader;
 pub use loader::SourceLoader;
-pub use source_utils::{collect_images_from_dir, is_image_file};
+pub use source_utils::{collect_images_from_dir, is_image_file, is_stream_url, is_video_file};
+pub use video::VideoFrameReader;
+pub use video_writer::VideoFrameWriter;
 
 // -- external imports
 use image::DynamicImage;
@@ -69,6 +73,14 @@ pub enum Source {
 
     /// List of images in memory
     ImageVec(Vec<DynamicImage>),
+
+    /// Path to a video file, decoded frame-by-frame
+    Video(PathBuf),
+
+    /// Live stream URL (e.g. `rtsp://...`), decoded frame-by-frame. The string is handed to
+    /// ffmpeg as its input unchanged; a bare camera index is not mapped to a capture device.
+    /// Unlike every other variant, the number of frames is not known ahead of time.
+    Stream(String),
 }
 
 impl std::fmt::Debug for Source {
@@ -80,6 +92,8 @@ impl std::fmt::Debug for Source {
             Source::ImagePathVec(v) => write!(f, "ImagePathVec({} items)", v.len()),
             Source::Image(img) => write!(f, "Image({}x{})", img.width(), img.height()),
             Source::ImageVec(v) => write!(f, "ImageVec({} items)", v.len()),
+            Source::Video(p) => write!(f, "Video({:?})", p),
+            Source::Stream(url) => write!(f, "Stream({url})"),
         }
     }
 }
@@ -100,12 +114,20 @@ impl Source {
     pub fn is_image(&self) -> bool {
         matches!(self, Source::ImagePath(_) | Source::Image(_))
     }
+
+    /// Returns true if source is a video file or a live stream (frames are decoded lazily
+    /// rather than enumerated up front).
+    pub fn is_stream(&self) -> bool {
+        matches!(self, Source::Video(_) | Source::Stream(_))
+    }
 }
 
 impl From<PathBuf> for Source {
     fn from(path: PathBuf) -> Self {
         if path.is_dir() {
             Source::Directory(path)
+        } else if source_utils::is_video_file(&path) {
+            Source::Video(path)
         } else {
             Source::ImagePath(path)
         }
@@ -114,13 +136,17 @@ impl From<PathBuf> for Source {
 
 impl From<&str> for Source {
     fn from(path: &str) -> Self {
-        Source::from(PathBuf::from(path))
+        if source_utils::is_stream_url(path) {
+            Source::Stream(path.to_string())
+        } else {
+            Source::from(PathBuf::from(path))
+        }
     }
 }
 
 impl From<String> for Source {
     fn from(path: String) -> Self {
-        Source::from(PathBuf::from(path))
+        Source::from(path.as_str())
     }
 }
 
@@ -148,36 +174,37 @@ impl Default for Source {
     }
 }
 
-/// Custom deserializer for Source from toml
-/// Only supports PathBuf-based variants (ImagePath, Directory, ImagePathVec)
-/// Empty string results in Source::None
+/// Custom deserializer for Source from toml.
+/// A single string is routed through `From<&str>`, so a stream URL (e.g. `rtsp://...`) becomes
+/// `Source::Stream` rather than being treated as a file path; a list of strings always becomes
+/// `Source::ImagePathVec`, since a stream URL doesn't make sense as one of several paths.
+/// Empty string/list results in Source::None.
 pub fn deserialize_source<'de, D>(deserializer: D) -> Result<Source, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    // Try to deserialize as PathBuf first
     #[derive(Deserialize)]
     #[serde(untagged)]
-    enum PathOrVec {
-        Path(PathBuf),
-        Vec(Vec<PathBuf>),
+    enum StringOrVec {
+        String(String),
+        Vec(Vec<String>),
     }
 
-    match PathOrVec::deserialize(deserializer)? {
-        PathOrVec::Path(path) => {
+    match StringOrVec::deserialize(deserializer)? {
+        StringOrVec::String(path) => {
             // Empty path becomes None
-            if path.as_os_str().is_empty() {
+            if path.is_empty() {
                 Ok(Source::None)
             } else {
-                Ok(path.into())
+                Ok(Source::from(path.as_str()))
             }
         }
-        PathOrVec::Vec(paths) => {
+        StringOrVec::Vec(paths) => {
             // Empty vec becomes None
             if paths.is_empty() {
                 Ok(Source::None)
             } else {
-                Ok(paths.into())
+                Ok(Source::ImagePathVec(paths.into_iter().map(PathBuf::from).collect()))
             }
         }
     }
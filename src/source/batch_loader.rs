@@ -1,12 +1,18 @@
 use image::DynamicImage;
 use std::iter::ExactSizeIterator;
 use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
 
 use crate::error::Result;
 
 use super::source_utils::{collect_images_from_dir, is_image_file};
+use super::video::VideoFrameReader;
 use super::{Source, SourceMeta};
 
+/// Default number of batches decoded ahead of the consumer.
+pub const DEFAULT_PREFETCH_DEPTH: usize = 2;
+
 #[derive(Debug, Clone)]
 enum FrameData {
     Path(PathBuf),
@@ -14,21 +20,86 @@ enum FrameData {
     None, // Padding for incomplete batches
 }
 
+type Batch = (Vec<DynamicImage>, Vec<SourceMeta>);
+
+/// Where `BatchSourceLoader` pulls its raw frames from.
+enum FrameProducer {
+    /// Frames are enumerated up front and decoded by a background thread that stays
+    /// `prefetch_depth` batches ahead of the consumer, handing completed batches over a
+    /// bounded channel so image I/O/decode overlaps with inference on the previous batch.
+    Materialized {
+        total_batches: usize,
+        rx: mpsc::Receiver<Batch>,
+        decode_handle: Option<thread::JoinHandle<()>>,
+    },
+    /// Frames are decoded lazily from a video file or live stream and chunked into batches on
+    /// demand, since the total length may be unknown ahead of time.
+    Streaming(VideoFrameReader),
+}
+
+impl std::fmt::Debug for FrameProducer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameProducer::Materialized { total_batches, .. } => {
+                write!(f, "Materialized({total_batches} batches)")
+            }
+            FrameProducer::Streaming(_) => write!(f, "Streaming"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BatchSourceLoader {
-    current_idx: usize,
-    batches: Vec<Vec<FrameData>>,
-    len: usize,
+    producer: FrameProducer,
     batch_size: usize,
-    total_frames: usize,
+    /// Total frames, if known ahead of time. Always `Some` for image-backed sources; may be
+    /// `None` for a video/stream source whose length can't be determined up front.
+    total_frames: Option<usize>,
+    current_idx: usize,
 }
 
 impl BatchSourceLoader {
+    /// Build a loader with the default prefetch depth (`DEFAULT_PREFETCH_DEPTH`).
     pub fn new(source: &Source, batch_size: Option<usize>) -> Result<Self> {
+        Self::new_with_prefetch(source, batch_size, DEFAULT_PREFETCH_DEPTH)
+    }
+
+    /// Build a loader, decoding up to `prefetch_depth` batches ahead of the consumer on a
+    /// background thread. Only affects image-backed sources; a video/stream source already
+    /// decodes frames lazily one at a time.
+    pub fn new_with_prefetch(
+        source: &Source,
+        batch_size: Option<usize>,
+        prefetch_depth: usize,
+    ) -> Result<Self> {
         let batch_size = match batch_size {
             Some(size) if size > 0 => size,
             _ => 1,
         };
+        let prefetch_depth = prefetch_depth.max(1);
+
+        match source {
+            Source::Video(path) => {
+                let reader = VideoFrameReader::open_file(path)?;
+                let total_frames = reader.total_frames();
+                return Ok(Self {
+                    producer: FrameProducer::Streaming(reader),
+                    batch_size,
+                    total_frames,
+                    current_idx: 0,
+                });
+            }
+            Source::Stream(url) => {
+                let reader = VideoFrameReader::open_stream(url)?;
+                return Ok(Self {
+                    producer: FrameProducer::Streaming(reader),
+                    batch_size,
+                    total_frames: None,
+                    current_idx: 0,
+                });
+            }
+            _ => {}
+        }
 
         let (batches, num_pads) = match source {
             Source::ImagePath(p) => {
@@ -60,15 +131,32 @@ impl BatchSourceLoader {
                     imgs.iter().cloned().map(FrameData::Image).collect();
                 Self::pad_and_chunk(frames_vec, batch_size)
             }
+            Source::Video(_) | Source::Stream(_) => unreachable!("handled above"),
         };
-        let len = batches.len();
-        let total_frames = len * batch_size - num_pads;
+
+        let total_batches = batches.len();
+        let total_frames = Some(total_batches * batch_size - num_pads);
+
+        let (tx, rx) = mpsc::sync_channel::<Batch>(prefetch_depth);
+        let decode_handle = thread::spawn(move || {
+            for (batch_idx, batch_frames) in batches.into_iter().enumerate() {
+                let batch = Self::decode_batch(batch_idx, &batch_frames, batch_size, total_frames);
+                if tx.send(batch).is_err() {
+                    // Consumer stopped early (loader was dropped); stop decoding.
+                    break;
+                }
+            }
+        });
+
         Ok(Self {
-            current_idx: 0,
-            batches,
-            len,
+            producer: FrameProducer::Materialized {
+                total_batches,
+                rx,
+                decode_handle: Some(decode_handle),
+            },
             batch_size,
             total_frames,
+            current_idx: 0,
         })
     }
 
@@ -89,26 +177,16 @@ impl BatchSourceLoader {
         (chunks, num_pads)
     }
 
-    pub const fn len(&self) -> usize {
-        self.len
-    }
-
-    pub const fn total_frames(&self) -> usize {
-        self.total_frames
-    }
-}
-
-impl Iterator for BatchSourceLoader {
-    type Item = (Vec<DynamicImage>, Vec<SourceMeta>);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current_idx >= self.len {
-            return None;
-        }
-
-        let batch_frames = &self.batches[self.current_idx];
-        let mut batch_images = Vec::with_capacity(self.batch_size);
-        let mut batch_metas = Vec::with_capacity(self.batch_size);
+    /// Decode (or clone, for in-memory frames) one batch's worth of frames. Runs on the
+    /// prefetch thread for image-backed sources.
+    fn decode_batch(
+        batch_idx: usize,
+        batch_frames: &[FrameData],
+        batch_size: usize,
+        total_frames: Option<usize>,
+    ) -> Batch {
+        let mut batch_images = Vec::with_capacity(batch_size);
+        let mut batch_metas = Vec::with_capacity(batch_size);
 
         for (i, frame_data) in batch_frames.iter().enumerate() {
             match frame_data {
@@ -120,23 +198,20 @@ impl Iterator for BatchSourceLoader {
                             continue;
                         }
                     };
-                    let meta = SourceMeta {
-                        frame_idx: self.current_idx * self.batch_size + i,
-                        total_frames: self.len * self.batch_size,
-                        source_path: Some(p.clone()),
-                    };
                     batch_images.push(img);
-                    batch_metas.push(meta);
+                    batch_metas.push(SourceMeta {
+                        frame_idx: batch_idx * batch_size + i,
+                        total_frames: total_frames.unwrap_or(0),
+                        source_path: Some(p.clone()),
+                    });
                 }
                 FrameData::Image(img) => {
-                    let meta = SourceMeta {
-                        frame_idx: self.current_idx * self.batch_size + i,
-                        total_frames: self.len * self.batch_size,
-                        source_path: None,
-                    };
-                    // batch_data.push((img.clone(), meta));
                     batch_images.push(img.clone());
-                    batch_metas.push(meta);
+                    batch_metas.push(SourceMeta {
+                        frame_idx: batch_idx * batch_size + i,
+                        total_frames: total_frames.unwrap_or(0),
+                        source_path: None,
+                    });
                 }
                 FrameData::None => {
                     // Skip padding frames
@@ -144,14 +219,98 @@ impl Iterator for BatchSourceLoader {
             }
         }
 
-        self.current_idx += 1;
-        Some((batch_images, batch_metas))
+        (batch_images, batch_metas)
+    }
+
+    /// Number of batches, if known ahead of time. Always accurate for image-backed sources;
+    /// `0` for a streaming source, since batches are produced on demand.
+    pub fn len(&self) -> usize {
+        match &self.producer {
+            FrameProducer::Materialized { total_batches, .. } => *total_batches,
+            FrameProducer::Streaming(_) => 0,
+        }
+    }
+
+    /// Total frames to process, or `None` for a live stream whose length is unknown.
+    pub const fn total_frames(&self) -> Option<usize> {
+        self.total_frames
+    }
+
+    /// Retune the chunk size requested from the producer going forward.
+    ///
+    /// Only takes effect for a [`FrameProducer::Streaming`] source, which chunks frames into
+    /// batches lazily on every call to `next`. A [`FrameProducer::Materialized`] source has
+    /// already been pre-chunked up front by its background decode thread, so this is a no-op
+    /// for it; adaptive sizing is only meaningful for streaming sources anyway, since a
+    /// materialized source's total frame count is known ahead of time.
+    pub fn set_batch_size(&mut self, new_size: usize) {
+        self.batch_size = new_size.max(1);
+    }
+}
+
+impl Drop for BatchSourceLoader {
+    fn drop(&mut self) {
+        if let FrameProducer::Materialized { rx, decode_handle, .. } = &mut self.producer {
+            // A struct's fields are only dropped after this method returns, so if the decode
+            // thread is still blocked on `tx.send()` (more batches prefetched than were ever
+            // consumed), joining it first would hang forever waiting for a `recv()` that will
+            // never come. Drop the receiver now instead: closing it makes `send` fail, which
+            // lets the thread exit, which is what `join` below is actually waiting for.
+            *rx = mpsc::channel().1;
+
+            if let Some(handle) = decode_handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+impl Iterator for BatchSourceLoader {
+    type Item = Batch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch_size = self.batch_size;
+        let current_idx = self.current_idx;
+        let total_frames = self.total_frames;
+
+        match &mut self.producer {
+            FrameProducer::Materialized { rx, .. } => {
+                let batch = rx.recv().ok();
+                if batch.is_some() {
+                    self.current_idx += 1;
+                }
+                batch
+            }
+            FrameProducer::Streaming(reader) => {
+                let mut batch_images = Vec::with_capacity(batch_size);
+                let mut batch_metas = Vec::with_capacity(batch_size);
+
+                for _ in 0..batch_size {
+                    let Some(img) = reader.next() else { break };
+                    batch_metas.push(SourceMeta {
+                        frame_idx: current_idx + batch_images.len(),
+                        total_frames: total_frames.unwrap_or(0),
+                        source_path: None,
+                    });
+                    batch_images.push(img);
+                }
+
+                if batch_images.is_empty() {
+                    return None;
+                }
+
+                self.current_idx += batch_images.len();
+                Some((batch_images, batch_metas))
+            }
+        }
     }
 }
 
-/// Implement ExactSizeIterator (to use indicatif's ProgressIterator)
+/// Implement ExactSizeIterator (to use indicatif's ProgressIterator).
+/// Only meaningful when `total_frames()` is `Some`; for a streaming source this is a
+/// best-effort `0` since the true length is unknown ahead of time.
 impl ExactSizeIterator for BatchSourceLoader {
     fn len(&self) -> usize {
-        self.len
+        BatchSourceLoader::len(self)
     }
 }
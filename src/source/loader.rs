@@ -1,28 +1,138 @@
 use image::DynamicImage;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::iter::ExactSizeIterator;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::Result;
 
 use super::source_utils::{collect_images_from_dir, is_image_file};
+use super::video::VideoFrameReader;
 use super::{Source, SourceMeta};
 
+/// Decoded-image disk cache for [`SourceLoader`]: resizes a loaded image to `target_size` once
+/// and reuses the resized file on every later run against the same source, instead of
+/// re-decoding and re-resizing it from scratch each time.
+///
+/// Cache entries are keyed by the source path's canonicalized form, its modification time and
+/// the target resolution, so the entry is invalidated automatically if the file on disk changes
+/// or a different `imgsz` is requested.
+#[derive(Debug, Clone)]
+struct ImageCache {
+    dir: PathBuf,
+    target_size: Option<usize>,
+}
+
+impl ImageCache {
+    fn cache_path(&self, path: &Path) -> Option<PathBuf> {
+        let abs_path = std::fs::canonicalize(path).ok()?;
+        let mtime = std::fs::metadata(path).ok()?.modified().ok()?;
+
+        let mut hasher = DefaultHasher::new();
+        abs_path.hash(&mut hasher);
+        mtime.hash(&mut hasher);
+        self.target_size.hash(&mut hasher);
+        let key = hasher.finish();
+
+        Some(self.dir.join(format!("{key:016x}.png")))
+    }
+
+    /// Load `path` through the cache, resizing to `target_size` (if set) on a cache miss and
+    /// writing the result back for next time. Falls back to decoding `path` directly (without
+    /// caching) if the cache can't be read or written for any reason.
+    fn load(&self, path: &Path) -> image::ImageResult<DynamicImage> {
+        let cache_path = self.cache_path(path);
+
+        if let Some(cache_path) = &cache_path
+            && cache_path.is_file()
+            && let Ok(img) = image::open(cache_path)
+        {
+            return Ok(img);
+        }
+
+        let img = image::open(path)?;
+        let img = match self.target_size {
+            Some(size) => img.resize(size as u32, size as u32, image::imageops::FilterType::Triangle),
+            None => img,
+        };
+
+        if let Some(cache_path) = cache_path {
+            if let Err(e) = std::fs::create_dir_all(&self.dir) {
+                tracing::warn!("Failed to create image cache directory {:?}: {}", self.dir, e);
+            } else if let Err(e) = img.save(&cache_path) {
+                tracing::warn!("Failed to write cached image {:?}: {}", cache_path, e);
+            }
+        }
+
+        Ok(img)
+    }
+}
+
 #[derive(Debug, Clone)]
 enum FrameData {
     Path(PathBuf),
     Image(DynamicImage),
 }
 
+/// Where `SourceLoader` pulls its raw frames from.
+enum FrameProducer {
+    /// Frames are enumerated up front (images/directories/in-memory vectors).
+    Materialized(Vec<FrameData>),
+    /// Frames are decoded lazily from a video file or live stream, since the total length may
+    /// be unknown ahead of time.
+    Streaming(VideoFrameReader),
+}
+
+impl std::fmt::Debug for FrameProducer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameProducer::Materialized(frames) => write!(f, "Materialized({} frames)", frames.len()),
+            FrameProducer::Streaming(_) => write!(f, "Streaming"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SourceLoader {
+    producer: FrameProducer,
     current_idx: usize,
-    frames: Vec<FrameData>,
-    len: usize,
+    /// Total frames, if known ahead of time. Always `Some` for image-backed sources; may be
+    /// `None` for a video/stream source whose length can't be determined up front.
+    total_frames: Option<usize>,
+    /// Decoded-image disk cache, if the caller configured one. Only consulted for `FrameData::Path`
+    /// frames — in-memory images have nothing on disk to key a cache entry off of.
+    cache: Option<ImageCache>,
 }
 
 impl SourceLoader {
-    pub fn new(source: &Source) -> Result<Self> {
+    /// Create a loader for `source`. If `cache_dir` is set, decoded-and-resized images are
+    /// cached under it, keyed by path/mtime/`target_size` (see [`ImageCache`]); `target_size` is
+    /// typically `PredictArgs::imgsz`.
+    pub fn new(source: &Source, cache_dir: Option<PathBuf>, target_size: Option<usize>) -> Result<Self> {
+        let cache = cache_dir.map(|dir| ImageCache { dir, target_size });
+
+        if let Source::Video(path) = source {
+            let reader = VideoFrameReader::open_file(path)?;
+            let total_frames = reader.total_frames();
+            return Ok(Self {
+                producer: FrameProducer::Streaming(reader),
+                current_idx: 0,
+                total_frames,
+                cache,
+            });
+        }
+        if let Source::Stream(url) = source {
+            let reader = VideoFrameReader::open_stream(url)?;
+            return Ok(Self {
+                producer: FrameProducer::Streaming(reader),
+                current_idx: 0,
+                total_frames: None,
+                cache,
+            });
+        }
+
         let frames = match source {
+            Source::None => vec![],
             Source::ImagePath(path) => {
                 if is_image_file(path) {
                     vec![FrameData::Path(path.clone())]
@@ -42,18 +152,36 @@ impl SourceLoader {
                 .collect(),
             Source::Image(img) => vec![FrameData::Image(img.clone())],
             Source::ImageVec(imgs) => imgs.iter().cloned().map(FrameData::Image).collect(),
+            Source::Video(_) | Source::Stream(_) => unreachable!("handled above"),
         };
-        let len = frames.len();
+        let total_frames = Some(frames.len());
 
         Ok(Self {
+            producer: FrameProducer::Materialized(frames),
             current_idx: 0,
-            frames,
-            len,
+            total_frames,
+            cache,
         })
     }
 
-    pub const fn len(&self) -> usize {
-        self.len
+    /// Number of frames, if known ahead of time. `0` for a streaming source whose length isn't
+    /// known; use [`Self::total_frames`] to tell "empty" apart from "unknown".
+    pub fn len(&self) -> usize {
+        self.total_frames.unwrap_or(0)
+    }
+
+    /// Total frames to process, or `None` for a live stream whose length is unknown.
+    pub const fn total_frames(&self) -> Option<usize> {
+        self.total_frames
+    }
+
+    /// Frames-per-second to pace a re-encoded output video at: the source video/stream's
+    /// nominal rate, or a reasonable default for image-backed sources.
+    pub fn fps(&self) -> f64 {
+        match &self.producer {
+            FrameProducer::Streaming(reader) => reader.fps(),
+            FrameProducer::Materialized(_) => 30.0,
+        }
     }
 }
 
@@ -62,26 +190,41 @@ impl Iterator for SourceLoader {
 
     /// Get the next image and its metadata (in lazy loading manner)
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current_idx >= self.len {
-            return None;
-        }
+        let current_idx = self.current_idx;
+        let total_frames = self.total_frames;
 
-        let frame_data = &self.frames[self.current_idx];
-        let (image, source_path) = match frame_data {
-            FrameData::Path(p) => match image::open(p) {
-                Ok(img) => (img, Some(p.clone())),
-                Err(e) => {
-                    tracing::error!("Failed to open image: {:?}. Error: {}", p, e);
-                    self.current_idx += 1;
-                    return self.next();
+        let (image, source_path) = match &mut self.producer {
+            FrameProducer::Materialized(frames) => {
+                if current_idx >= frames.len() {
+                    return None;
+                }
+                match &frames[current_idx] {
+                    FrameData::Path(p) => {
+                        let result = match &self.cache {
+                            Some(cache) => cache.load(p),
+                            None => image::open(p),
+                        };
+                        match result {
+                            Ok(img) => (img, Some(p.clone())),
+                            Err(e) => {
+                                tracing::error!("Failed to open image: {:?}. Error: {}", p, e);
+                                self.current_idx += 1;
+                                return self.next();
+                            }
+                        }
+                    }
+                    FrameData::Image(img) => (img.clone(), None),
                 }
-            },
-            FrameData::Image(img) => (img.clone(), None),
+            }
+            FrameProducer::Streaming(reader) => {
+                let img = reader.next()?;
+                (img, None)
+            }
         };
 
         let meta = SourceMeta {
-            frame_idx: self.current_idx,
-            total_frames: self.len,
+            frame_idx: current_idx,
+            total_frames: total_frames.unwrap_or(0),
             source_path,
         };
 
@@ -90,9 +233,11 @@ impl Iterator for SourceLoader {
     }
 }
 
-/// Implement ExactSizeIterator (to use indicatif's ProgressIterator)
+/// Implement ExactSizeIterator (to use indicatif's ProgressIterator).
+/// Only meaningful when `total_frames()` is `Some`; for a streaming source this is a
+/// best-effort `0` since the true length is unknown ahead of time.
 impl ExactSizeIterator for SourceLoader {
     fn len(&self) -> usize {
-        self.len
+        SourceLoader::len(self)
     }
 }
@@ -11,6 +11,25 @@ pub fn is_image_file(path: &PathBuf) -> bool {
     })
 }
 
+/// Returns true if `path` has a common video container extension.
+pub fn is_video_file(path: &PathBuf) -> bool {
+    path.extension().is_some_and(|ext| {
+        let ext = ext.to_string_lossy().to_lowercase();
+        matches!(
+            ext.as_str(),
+            "mp4" | "mov" | "avi" | "mkv" | "webm" | "flv" | "wmv" | "m4v"
+        )
+    })
+}
+
+/// Returns true if `s` looks like a live camera/stream URL (`rtsp://`, `rtmp://`, `http(s)://`)
+/// rather than a file path.
+pub fn is_stream_url(s: &str) -> bool {
+    ["rtsp://", "rtmp://", "http://", "https://"]
+        .iter()
+        .any(|prefix| s.starts_with(prefix))
+}
+
 pub fn collect_images_from_dir(dir: &PathBuf) -> Result<Vec<PathBuf>> {
     let mut image_paths = vec![];
     for entry in std::fs::read_dir(dir)? {
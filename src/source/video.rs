@@ -0,0 +1,190 @@
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::format::Pixel;
+use ffmpeg_next::software::scaling::{Context as ScalingContext, Flags as ScalingFlags};
+use image::{DynamicImage, RgbImage};
+use std::path::PathBuf;
+
+use crate::error::{AppError, Result};
+
+/// Lazily decodes frames from a video file or a live camera/RTSP stream.
+///
+/// Frames are pulled one at a time from the underlying demuxer/decoder instead of being
+/// materialized up front, so this works equally well for an `.mp4` file (where `total_frames`
+/// can usually be estimated from the container) and for an unbounded live stream (where it
+/// cannot).
+pub struct VideoFrameReader {
+    input: ffmpeg::format::context::Input,
+    decoder: ffmpeg::decoder::Video,
+    scaler: ScalingContext,
+    stream_index: usize,
+    total_frames: Option<usize>,
+    fps: f64,
+    finished: bool,
+    /// Whether `send_eof` has already been sent to the decoder, to flush frames it's still
+    /// holding for B-frame reordering/lookahead once the demuxer runs out of packets.
+    eof_sent: bool,
+}
+
+impl VideoFrameReader {
+    /// Open a local video file for frame-by-frame decoding.
+    pub fn open_file(path: &PathBuf) -> Result<Self> {
+        if !path.is_file() {
+            return Err(AppError::ImageLoad(format!(
+                "Video file not found: {:?}",
+                path
+            )));
+        }
+        let total_frames = Self::probe_frame_count(path);
+        Self::open(path.to_string_lossy().as_ref(), total_frames)
+    }
+
+    /// Open a camera/RTSP/RTMP stream URL for frame-by-frame decoding.
+    /// The stream length is unknown ahead of time, so `total_frames` is always `None`.
+    pub fn open_stream(url: &str) -> Result<Self> {
+        Self::open(url, None)
+    }
+
+    fn open(url: &str, total_frames: Option<usize>) -> Result<Self> {
+        ffmpeg::init().map_err(|e| AppError::ImageLoad(format!("ffmpeg init failed: {e}")))?;
+
+        let input = ffmpeg::format::input(&url)
+            .map_err(|e| AppError::ImageLoad(format!("Failed to open video source: {e}")))?;
+
+        let stream = input
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .ok_or_else(|| AppError::ImageLoad("No video stream found".to_string()))?;
+        let stream_index = stream.index();
+        // A live stream's nominal rate is often unreliable, but it's still the best guess
+        // available for muxing annotated frames back out at a sensible pace.
+        let fps = f64::from(stream.rate());
+
+        let decoder_ctx = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+            .map_err(|e| AppError::ImageLoad(format!("Failed to create decoder: {e}")))?;
+        let decoder = decoder_ctx
+            .decoder()
+            .video()
+            .map_err(|e| AppError::ImageLoad(format!("Failed to open video decoder: {e}")))?;
+
+        let scaler = ScalingContext::get(
+            decoder.format(),
+            decoder.width(),
+            decoder.height(),
+            Pixel::RGB24,
+            decoder.width(),
+            decoder.height(),
+            ScalingFlags::BILINEAR,
+        )
+        .map_err(|e| AppError::ImageLoad(format!("Failed to create frame scaler: {e}")))?;
+
+        Ok(Self {
+            input,
+            decoder,
+            scaler,
+            stream_index,
+            total_frames,
+            fps,
+            finished: false,
+            eof_sent: false,
+        })
+    }
+
+    /// Best-effort frame count from the video stream's frame count metadata.
+    /// Returns `None` when the container doesn't record it.
+    fn probe_frame_count(path: &PathBuf) -> Option<usize> {
+        let ictx = ffmpeg::format::input(&path).ok()?;
+        let stream = ictx.streams().best(ffmpeg::media::Type::Video)?;
+        let frames = stream.frames();
+        if frames > 0 {
+            Some(frames as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Total frame count if known ahead of time (files only; streams are always `None`).
+    pub const fn total_frames(&self) -> Option<usize> {
+        self.total_frames
+    }
+
+    /// Nominal frames-per-second reported by the source, used to pace a re-encoded output video
+    /// via [`super::video_writer::VideoFrameWriter`].
+    pub const fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    fn decode_one(&mut self) -> Option<DynamicImage> {
+        let mut decoded = ffmpeg::frame::Video::empty();
+
+        // Pull packets until the decoder yields a full frame or the source is exhausted. A
+        // corrupt packet/frame is logged and skipped rather than ending the stream.
+        loop {
+            if self.decoder.receive_frame(&mut decoded).is_ok() {
+                let mut rgb_frame = ffmpeg::frame::Video::empty();
+                if let Err(e) = self.scaler.run(&decoded, &mut rgb_frame) {
+                    tracing::warn!("Failed to scale decoded video frame, skipping: {e}");
+                    continue;
+                }
+                match Self::frame_to_image(&rgb_frame) {
+                    Some(img) => return Some(img),
+                    None => {
+                        tracing::warn!("Failed to convert decoded video frame, skipping");
+                        continue;
+                    }
+                }
+            }
+
+            let Some((stream, packet)) = self.input.packets().next() else {
+                // Demuxer exhausted: the decoder may still be holding buffered frames for
+                // B-frame reordering/encoder lookahead. Flush them with `send_eof` before
+                // reporting the stream as done, so the last few frames aren't silently dropped.
+                if !self.eof_sent {
+                    self.eof_sent = true;
+                    let _ = self.decoder.send_eof();
+                    continue;
+                }
+                return None;
+            };
+            if stream.index() != self.stream_index {
+                continue;
+            }
+            if let Err(e) = self.decoder.send_packet(&packet) {
+                tracing::warn!("Failed to decode video packet, skipping: {e}");
+                continue;
+            }
+        }
+    }
+
+    fn frame_to_image(frame: &ffmpeg::frame::Video) -> Option<DynamicImage> {
+        let width = frame.width();
+        let height = frame.height();
+        let stride = frame.stride(0);
+        let data = frame.data(0);
+
+        let mut buf = Vec::with_capacity((width * height * 3) as usize);
+        for row in 0..height as usize {
+            let start = row * stride;
+            buf.extend_from_slice(&data[start..start + width as usize * 3]);
+        }
+
+        RgbImage::from_raw(width, height, buf).map(DynamicImage::ImageRgb8)
+    }
+}
+
+impl Iterator for VideoFrameReader {
+    type Item = DynamicImage;
+
+    /// Decode and return the next frame, or `None` once the file/stream is exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.decode_one() {
+            Some(img) => Some(img),
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::format::Pixel;
+use ffmpeg_next::software::scaling::{Context as ScalingContext, Flags as ScalingFlags};
+use image::DynamicImage;
+use std::path::Path;
+
+use crate::error::{AppError, Result};
+
+/// Muxes annotated frames into an output video file as they arrive, instead of writing one PNG
+/// per frame. The encode-side mirror of [`super::video::VideoFrameReader`].
+pub struct VideoFrameWriter {
+    output: ffmpeg::format::context::Output,
+    encoder: ffmpeg::encoder::video::Video,
+    scaler: ScalingContext,
+    stream_index: usize,
+    time_base: ffmpeg::Rational,
+    /// Duration of one frame, in `time_base` units, used to space out each frame's PTS so
+    /// playback runs at `fps` rather than at one frame per `time_base` tick.
+    frame_duration: i64,
+    frame_count: i64,
+}
+
+impl VideoFrameWriter {
+    /// Open `path` for writing and start an H.264 stream sized `width x height`, paced at `fps`
+    /// frames per second. The container is guessed from `path`'s extension (e.g. `.mp4`).
+    pub fn create(path: &Path, width: u32, height: u32, fps: f64) -> Result<Self> {
+        ffmpeg::init().map_err(|e| AppError::ImageLoad(format!("ffmpeg init failed: {e}")))?;
+
+        let mut output = ffmpeg::format::output(&path)
+            .map_err(|e| AppError::ImageLoad(format!("Failed to create output video: {e}")))?;
+
+        let codec = ffmpeg::encoder::find(ffmpeg::codec::Id::H264)
+            .ok_or_else(|| AppError::ImageLoad("No H.264 encoder available".to_string()))?;
+        let mut stream = output
+            .add_stream(codec)
+            .map_err(|e| AppError::ImageLoad(format!("Failed to add output video stream: {e}")))?;
+        let stream_index = stream.index();
+
+        // A 1/1000 time base gives millisecond-granularity PTS, which is fine-grained enough
+        // for any realistic fps while staying simple to reason about.
+        let time_base = ffmpeg::Rational::new(1, 1000);
+        let fps = if fps > 0.0 { fps } else { 30.0 };
+
+        let encoder_ctx = ffmpeg::codec::context::Context::new_with_codec(codec);
+        let mut encoder = encoder_ctx
+            .encoder()
+            .video()
+            .map_err(|e| AppError::ImageLoad(format!("Failed to create output video encoder: {e}")))?;
+        encoder.set_width(width);
+        encoder.set_height(height);
+        encoder.set_format(Pixel::YUV420P);
+        encoder.set_time_base(time_base);
+        encoder.set_frame_rate(Some(ffmpeg::Rational::new(fps.round() as i32, 1)));
+        if output.format().flags().contains(ffmpeg::format::Flags::GLOBAL_HEADER) {
+            encoder.set_flags(ffmpeg::codec::Flags::GLOBAL_HEADER);
+        }
+
+        let encoder = encoder
+            .open_as(codec)
+            .map_err(|e| AppError::ImageLoad(format!("Failed to open output video encoder: {e}")))?;
+
+        stream.set_time_base(time_base);
+        stream.set_parameters(&encoder);
+
+        output
+            .write_header()
+            .map_err(|e| AppError::ImageLoad(format!("Failed to write video header: {e}")))?;
+
+        let scaler = ScalingContext::get(
+            Pixel::RGB24,
+            width,
+            height,
+            Pixel::YUV420P,
+            width,
+            height,
+            ScalingFlags::BILINEAR,
+        )
+        .map_err(|e| AppError::ImageLoad(format!("Failed to create frame scaler: {e}")))?;
+
+        // 1000 (the time_base's denominator) divided by fps is how many 1ms ticks one frame
+        // should last; without this every frame would be spaced exactly 1 time_base tick apart
+        // (i.e. 1ms) regardless of fps, making playback run at ~1000fps-equivalent speed.
+        let frame_duration = (1000.0 / fps).round().max(1.0) as i64;
+
+        Ok(Self {
+            output,
+            encoder,
+            scaler,
+            stream_index,
+            time_base,
+            frame_duration,
+            frame_count: 0,
+        })
+    }
+
+    /// Encode and mux one more frame. Frames are written in the order they're passed in, paced
+    /// by the `fps` given to [`Self::create`].
+    pub fn write_frame(&mut self, image: &DynamicImage) -> Result<()> {
+        let rgb = image.to_rgb8();
+        let (width, height) = (rgb.width(), rgb.height());
+
+        let mut rgb_frame = ffmpeg::frame::Video::new(Pixel::RGB24, width, height);
+        rgb_frame.data_mut(0).copy_from_slice(&rgb);
+
+        let mut yuv_frame = ffmpeg::frame::Video::empty();
+        self.scaler
+            .run(&rgb_frame, &mut yuv_frame)
+            .map_err(|e| AppError::ImageLoad(format!("Failed to scale frame for encoding: {e}")))?;
+        yuv_frame.set_pts(Some(self.frame_count * self.frame_duration));
+        self.frame_count += 1;
+
+        self.encoder
+            .send_frame(&yuv_frame)
+            .map_err(|e| AppError::ImageLoad(format!("Failed to encode video frame: {e}")))?;
+        self.drain_packets()
+    }
+
+    fn drain_packets(&mut self) -> Result<()> {
+        let mut packet = ffmpeg::Packet::empty();
+        while self.encoder.receive_packet(&mut packet).is_ok() {
+            packet.set_stream(self.stream_index);
+            packet.rescale_ts(self.encoder.time_base(), self.time_base);
+            packet
+                .write_interleaved(&mut self.output)
+                .map_err(|e| AppError::ImageLoad(format!("Failed to write video packet: {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// Flush the encoder and finalize the container. Must be called once all frames have been
+    /// written; dropping the writer without calling this leaves a truncated/unplayable file.
+    pub fn finish(mut self) -> Result<()> {
+        self.encoder
+            .send_eof()
+            .map_err(|e| AppError::ImageLoad(format!("Failed to flush video encoder: {e}")))?;
+        self.drain_packets()?;
+        self.output
+            .write_trailer()
+            .map_err(|e| AppError::ImageLoad(format!("Failed to finalize output video: {e}")))
+    }
+}
@@ -1,12 +1,19 @@
 // -- imports
 use serde::Deserialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use strum::VariantNames;
 
 use crate::annotate::AnnotateConfigs;
 use crate::error::{AppError, Result};
+use crate::export::{OutputFormat, deserialize_output_format};
+use crate::infer_fn::InferFn;
 use crate::predict::PredictArgs;
 use crate::source::Source;
 
+/// Conventional config file name searched for by [`discover_and_parse`].
+pub const CONFIG_FILE_NAME: &str = "yolo-inference.toml";
+
 // -- config
 
 #[derive(Debug, Deserialize, Default)]
@@ -17,14 +24,6 @@ struct TomlConfig {
 }
 
 impl TomlConfig {
-    /// Parse TOML config file and return a TomlConfig instance.
-    ///
-    /// # Errors
-    ///
-    /// Returns `AppError` if:
-    /// - The path is not a valid toml file
-    /// - File read fails
-    /// - TOML parsing fails
     /// Parse TOML config file with explicit project root for path resolution.
     ///
     /// # Arguments
@@ -48,46 +47,305 @@ impl TomlConfig {
 
         let content = std::fs::read_to_string(toml_path)?;
         let mut config: Self = toml::from_str(&content)?;
-        config.resolve_paths(project_root);
+        resolve_paths(&mut config.predict, project_root);
 
         // Transfer annotate config to predict args
         config.predict.annotate_cfg = config.annotate.clone();
 
         Ok(config)
     }
+}
+
+impl From<TomlConfig> for PredictArgs {
+    fn from(config: TomlConfig) -> Self {
+        config.predict
+    }
+}
+
+/// Resolve relative paths in `predict` against `project_root`, in place.
+fn resolve_paths(predict: &mut PredictArgs, project_root: &Path) {
+    // Resolve model path
+    if !predict.model.is_absolute() {
+        predict.model = project_root.join(&predict.model);
+    }
 
-    /// Resolve relative paths against project root
-    fn resolve_paths(&mut self, project_root: &Path) {
-        // Resolve model path
-        if !self.predict.model.is_absolute() {
-            self.predict.model = project_root.join(&self.predict.model);
+    // Resolve source path (skip if None)
+    predict.source = match &predict.source {
+        Source::None => Source::None,
+        Source::ImagePath(p) if !p.is_absolute() => Source::ImagePath(project_root.join(p)),
+        Source::Directory(p) if !p.is_absolute() => Source::Directory(project_root.join(p)),
+        Source::Video(p) if !p.is_absolute() => Source::Video(project_root.join(p)),
+        Source::ImagePathVec(paths) => {
+            Source::ImagePathVec(paths.iter().map(|p| project_root.join(p)).collect())
         }
+        _ => predict.source.clone(),
+    };
 
-        // Resolve source path (skip if None)
-        self.predict.source = match &self.predict.source {
-            Source::None => Source::None,
-            Source::ImagePath(p) if !p.is_absolute() => Source::ImagePath(project_root.join(p)),
-            Source::Directory(p) if !p.is_absolute() => Source::Directory(project_root.join(p)),
-            _ => self.predict.source.clone(),
-        };
+    // Resolve save_dir
+    if let Some(ref mut save_dir) = predict.save_dir {
+        if !save_dir.is_absolute() {
+            *save_dir = project_root.join(save_dir.as_path());
+        }
+    }
 
-        // Resolve save_dir
-        if let Some(ref mut save_dir) = self.predict.save_dir {
-            if !save_dir.is_absolute() {
-                *save_dir = project_root.join(save_dir.as_path());
-            }
+    // Resolve cache_dir
+    if let Some(ref mut cache_dir) = predict.cache_dir {
+        if !cache_dir.is_absolute() {
+            *cache_dir = project_root.join(cache_dir.as_path());
         }
     }
 }
 
-impl From<TomlConfig> for PredictArgs {
-    fn from(config: TomlConfig) -> Self {
-        config.predict
+// -- layered config
+
+/// All-`Option` mirror of [`PredictArgs`], used to tell "absent from this layer" (`None`) apart
+/// from "set to this value" (`Some`) when merging multiple TOML layers together.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct PredictArgsLayer {
+    model: Option<PathBuf>,
+    #[serde(default, deserialize_with = "deserialize_source_layer")]
+    source: Option<Source>,
+    conf: Option<f32>,
+    iou: Option<f32>,
+    max_det: Option<usize>,
+    imgsz: Option<usize>,
+    half: Option<bool>,
+    batch: Option<usize>,
+    adaptive_batch: Option<bool>,
+    batch_min: Option<usize>,
+    batch_max: Option<usize>,
+    device: Option<String>,
+    save_dir: Option<PathBuf>,
+    save_as_video: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_output_format")]
+    output_format: Option<OutputFormat>,
+    cache_dir: Option<PathBuf>,
+    #[serde(default, deserialize_with = "deserialize_infer_fn_layer")]
+    infer_fn: Option<InferFn>,
+    tracking: Option<bool>,
+    track_max_age: Option<usize>,
+    track_iou_threshold: Option<f32>,
+    annotate: Option<bool>,
+    channel_capacity: Option<usize>,
+    infer_workers: Option<usize>,
+    return_result: Option<bool>,
+    verbose: Option<bool>,
+}
+
+impl PredictArgsLayer {
+    /// Fold `other` (a later, more specific layer) onto `self`, with `other`'s fields winning
+    /// wherever it sets them.
+    fn merge(self, other: Self) -> Self {
+        Self {
+            model: other.model.or(self.model),
+            source: other.source.or(self.source),
+            conf: other.conf.or(self.conf),
+            iou: other.iou.or(self.iou),
+            max_det: other.max_det.or(self.max_det),
+            imgsz: other.imgsz.or(self.imgsz),
+            half: other.half.or(self.half),
+            batch: other.batch.or(self.batch),
+            adaptive_batch: other.adaptive_batch.or(self.adaptive_batch),
+            batch_min: other.batch_min.or(self.batch_min),
+            batch_max: other.batch_max.or(self.batch_max),
+            device: other.device.or(self.device),
+            save_dir: other.save_dir.or(self.save_dir),
+            save_as_video: other.save_as_video.or(self.save_as_video),
+            output_format: other.output_format.or(self.output_format),
+            cache_dir: other.cache_dir.or(self.cache_dir),
+            infer_fn: other.infer_fn.or(self.infer_fn),
+            tracking: other.tracking.or(self.tracking),
+            track_max_age: other.track_max_age.or(self.track_max_age),
+            track_iou_threshold: other.track_iou_threshold.or(self.track_iou_threshold),
+            annotate: other.annotate.or(self.annotate),
+            channel_capacity: other.channel_capacity.or(self.channel_capacity),
+            infer_workers: other.infer_workers.or(self.infer_workers),
+            return_result: other.return_result.or(self.return_result),
+            verbose: other.verbose.or(self.verbose),
+        }
+    }
+
+    /// Collapse to a concrete [`PredictArgs`], filling any field no layer set with
+    /// `PredictArgs::default()`. `annotate_cfg` and `job` are left at their defaults; the former
+    /// is filled in separately from the merged annotate layer, the latter is never TOML-configurable.
+    fn into_predict_args(self) -> PredictArgs {
+        let defaults = PredictArgs::default();
+        PredictArgs {
+            model: self.model.unwrap_or(defaults.model),
+            source: self.source.unwrap_or(defaults.source),
+            conf: self.conf.unwrap_or(defaults.conf),
+            iou: self.iou.unwrap_or(defaults.iou),
+            max_det: self.max_det.unwrap_or(defaults.max_det),
+            imgsz: self.imgsz.or(defaults.imgsz),
+            half: self.half.unwrap_or(defaults.half),
+            batch: self.batch.or(defaults.batch),
+            adaptive_batch: self.adaptive_batch.unwrap_or(defaults.adaptive_batch),
+            batch_min: self.batch_min.or(defaults.batch_min),
+            batch_max: self.batch_max.or(defaults.batch_max),
+            device: self.device.or(defaults.device),
+            save_dir: self.save_dir.or(defaults.save_dir),
+            save_as_video: self.save_as_video.unwrap_or(defaults.save_as_video),
+            output_format: self.output_format.or(defaults.output_format),
+            cache_dir: self.cache_dir.or(defaults.cache_dir),
+            infer_fn: self.infer_fn.unwrap_or(defaults.infer_fn),
+            tracking: self.tracking.unwrap_or(defaults.tracking),
+            track_max_age: self.track_max_age.unwrap_or(defaults.track_max_age),
+            track_iou_threshold: self.track_iou_threshold.unwrap_or(defaults.track_iou_threshold),
+            annotate: self.annotate.unwrap_or(defaults.annotate),
+            annotate_cfg: defaults.annotate_cfg,
+            channel_capacity: self.channel_capacity.or(defaults.channel_capacity),
+            infer_workers: self.infer_workers.unwrap_or(defaults.infer_workers),
+            return_result: self.return_result.unwrap_or(defaults.return_result),
+            verbose: self.verbose.unwrap_or(defaults.verbose),
+            job: defaults.job,
+        }
     }
 }
 
+/// Layer-aware counterpart of `deserialize_source`: `None` means the key was absent from this
+/// layer, rather than "no source".
+fn deserialize_source_layer<'de, D>(deserializer: D) -> std::result::Result<Option<Source>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrVec {
+        String(String),
+        Vec(Vec<String>),
+    }
+
+    let value = Option::<StringOrVec>::deserialize(deserializer)?;
+    Ok(match value {
+        None => None,
+        Some(StringOrVec::String(path)) if path.is_empty() => Some(Source::None),
+        Some(StringOrVec::String(path)) => Some(Source::from(path.as_str())),
+        Some(StringOrVec::Vec(paths)) if paths.is_empty() => Some(Source::None),
+        Some(StringOrVec::Vec(paths)) => {
+            Some(Source::ImagePathVec(paths.into_iter().map(PathBuf::from).collect()))
+        }
+    })
+}
+
+/// Layer-aware counterpart of `deserialize_infer_fn`: `None` means the key was absent from this
+/// layer, rather than falling back to `InferFn`'s default.
+fn deserialize_infer_fn_layer<'de, D>(deserializer: D) -> std::result::Result<Option<InferFn>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(value) => InferFn::from_str(&value).map(Some).map_err(|_| {
+            let expected = crate::fuzzy::expected_variants(&value, InferFn::VARIANTS);
+            serde::de::Error::invalid_value(serde::de::Unexpected::Str(&value), &expected.as_str())
+        }),
+    }
+}
+
+/// All-`Option` mirror of [`AnnotateConfigs`], analogous to [`PredictArgsLayer`].
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct AnnotateConfigsLayer {
+    on_blank: Option<bool>,
+    show_box: Option<bool>,
+    show_label: Option<bool>,
+    show_conf: Option<bool>,
+    top_k: Option<usize>,
+    mask_alpha: Option<f32>,
+    show_mask_contour: Option<bool>,
+    obb_fill_alpha: Option<f32>,
+    kpt_conf_threshold: Option<f32>,
+    show_low_conf_kpts: Option<bool>,
+    show_kpt_labels: Option<bool>,
+}
+
+impl AnnotateConfigsLayer {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            on_blank: other.on_blank.or(self.on_blank),
+            show_box: other.show_box.or(self.show_box),
+            show_label: other.show_label.or(self.show_label),
+            show_conf: other.show_conf.or(self.show_conf),
+            top_k: other.top_k.or(self.top_k),
+            mask_alpha: other.mask_alpha.or(self.mask_alpha),
+            show_mask_contour: other.show_mask_contour.or(self.show_mask_contour),
+            obb_fill_alpha: other.obb_fill_alpha.or(self.obb_fill_alpha),
+            kpt_conf_threshold: other.kpt_conf_threshold.or(self.kpt_conf_threshold),
+            show_low_conf_kpts: other.show_low_conf_kpts.or(self.show_low_conf_kpts),
+            show_kpt_labels: other.show_kpt_labels.or(self.show_kpt_labels),
+        }
+    }
+
+    fn into_annotate_configs(self) -> AnnotateConfigs {
+        let defaults = AnnotateConfigs::default();
+        AnnotateConfigs {
+            on_blank: self.on_blank.unwrap_or(defaults.on_blank),
+            show_box: self.show_box.unwrap_or(defaults.show_box),
+            show_label: self.show_label.unwrap_or(defaults.show_label),
+            show_conf: self.show_conf.unwrap_or(defaults.show_conf),
+            top_k: self.top_k.or(defaults.top_k),
+            mask_alpha: self.mask_alpha.unwrap_or(defaults.mask_alpha),
+            show_mask_contour: self.show_mask_contour.unwrap_or(defaults.show_mask_contour),
+            obb_fill_alpha: self.obb_fill_alpha.unwrap_or(defaults.obb_fill_alpha),
+            kpt_conf_threshold: self.kpt_conf_threshold.unwrap_or(defaults.kpt_conf_threshold),
+            show_low_conf_kpts: self.show_low_conf_kpts.unwrap_or(defaults.show_low_conf_kpts),
+            show_kpt_labels: self.show_kpt_labels.unwrap_or(defaults.show_kpt_labels),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct TomlConfigLayer {
+    predict: PredictArgsLayer,
+    annotate: AnnotateConfigsLayer,
+}
+
 // -- public API
 
+/// Merge an ordered list of TOML config files into a single [`PredictArgs`], with later files
+/// overriding earlier ones field-by-field. Useful for layering a shared base config with
+/// machine- or run-specific overrides.
+///
+/// Relative paths in the result are resolved against `project_root`, once, after the merge.
+///
+/// # Errors
+///
+/// Returns `AppError::Config` if `paths` is empty, or `AppError` if any file is not a valid
+/// `.toml` file, can't be read, or fails to parse.
+pub fn parse_toml_layered(paths: &[&Path], project_root: &Path) -> Result<PredictArgs> {
+    if paths.is_empty() {
+        return Err(AppError::Config(
+            "parse_toml_layered requires at least one TOML path".to_string(),
+        ));
+    }
+
+    let mut predict_layer = PredictArgsLayer::default();
+    let mut annotate_layer = AnnotateConfigsLayer::default();
+
+    for &toml_path in paths {
+        if !toml_path.is_file() || toml_path.extension().map_or(false, |ext| ext != "toml") {
+            return Err(AppError::Config(format!(
+                "TOML config path is not a valid .toml file: {:?}",
+                toml_path
+            )));
+        }
+
+        let content = std::fs::read_to_string(toml_path)?;
+        let layer: TomlConfigLayer = toml::from_str(&content)?;
+        predict_layer = predict_layer.merge(layer.predict);
+        annotate_layer = annotate_layer.merge(layer.annotate);
+    }
+
+    let mut predict = predict_layer.into_predict_args();
+    predict.annotate_cfg = annotate_layer.into_annotate_configs();
+    resolve_paths(&mut predict, project_root);
+
+    Ok(predict)
+}
+
 /// Parse TOML config file and return PredictArgs.
 ///
 /// # Arguments
@@ -99,7 +357,36 @@ impl From<TomlConfig> for PredictArgs {
 ///
 /// Returns `AppError` if TOML parsing or path resolution fails.
 pub fn parse_toml(toml_path: &Path, project_root: &Path) -> Result<PredictArgs> {
-    TomlConfig::from_toml(toml_path, project_root).map(Into::into)
+    parse_toml_layered(&[toml_path], project_root)
+}
+
+/// Search `start_dir` and its ancestors for a [`CONFIG_FILE_NAME`] file, analogous to how Cargo
+/// locates a manifest, and parse it with the discovered file's directory as the project root, so
+/// relative paths in it stay anchored to where the config lives rather than the process's
+/// current working directory.
+///
+/// # Errors
+///
+/// Returns `AppError::Config` if no config file is found by the time the filesystem root is
+/// reached, or if parsing fails.
+pub fn discover_and_parse(start_dir: &Path) -> Result<PredictArgs> {
+    let mut dir = start_dir;
+    loop {
+        let candidate = dir.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return parse_toml(&candidate, dir);
+        }
+
+        dir = match dir.parent() {
+            Some(parent) => parent,
+            None => {
+                return Err(AppError::Config(format!(
+                    "No {} found in {:?} or any parent directory",
+                    CONFIG_FILE_NAME, start_dir
+                )));
+            }
+        };
+    }
 }
 
 // -- tests
@@ -235,4 +522,160 @@ show_box = true
         assert!(config.predict.source.is_none());
         assert_eq!(config.predict.conf, 0.5);
     }
+
+    #[test]
+    fn test_from_toml_with_stream_url_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml_path = temp_dir.path().join("config.toml");
+        let toml_content = r#"
+[predict]
+model = "test.onnx"
+source = "rtsp://example.com/stream"
+"#;
+        fs::write(&toml_path, toml_content).unwrap();
+
+        let config = TomlConfig::from_toml(&toml_path, temp_dir.path()).unwrap();
+
+        // A stream URL must route through `Source::Stream`, not be treated as a bogus file path.
+        assert!(matches!(config.predict.source, Source::Stream(_)));
+    }
+
+    #[test]
+    fn test_parse_toml_layered_with_stream_url_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &toml_path,
+            r#"
+[predict]
+model = "test.onnx"
+source = "rtsp://example.com/stream"
+"#,
+        )
+        .unwrap();
+
+        let args = parse_toml_layered(&[&toml_path], temp_dir.path()).unwrap();
+
+        assert!(matches!(args.source, Source::Stream(_)));
+    }
+
+    #[test]
+    fn test_discover_and_parse_finds_config_in_ancestor_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested_dir = temp_dir.path().join("a").join("b").join("c");
+        fs::create_dir_all(&nested_dir).unwrap();
+
+        let toml_content = r#"
+[predict]
+model = "test.onnx"
+conf = 0.6
+"#;
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), toml_content).unwrap();
+
+        let args = discover_and_parse(&nested_dir).unwrap();
+
+        assert_eq!(args.conf, 0.6);
+        // Relative `model` path should resolve against the config file's directory, not `nested_dir`.
+        assert_eq!(args.model, temp_dir.path().join("test.onnx"));
+    }
+
+    #[test]
+    fn test_discover_and_parse_no_config_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested_dir = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&nested_dir).unwrap();
+
+        assert!(discover_and_parse(&nested_dir).is_err());
+    }
+
+    #[test]
+    fn test_parse_toml_layered_override_wins_over_base() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let base_path = temp_dir.path().join("base.toml");
+        fs::write(
+            &base_path,
+            r#"
+[predict]
+model = "base.onnx"
+conf = 0.25
+max_det = 300
+
+[annotate]
+show_box = true
+mask_alpha = 0.3
+"#,
+        )
+        .unwrap();
+
+        let override_path = temp_dir.path().join("override.toml");
+        fs::write(
+            &override_path,
+            r#"
+[predict]
+conf = 0.9
+
+[annotate]
+mask_alpha = 0.8
+"#,
+        )
+        .unwrap();
+
+        let args = parse_toml_layered(&[&base_path, &override_path], temp_dir.path()).unwrap();
+
+        // Overridden fields take the later layer's value.
+        assert_eq!(args.conf, 0.9);
+        assert_eq!(args.annotate_cfg.mask_alpha, 0.8);
+
+        // Fields only set in the base layer persist.
+        assert_eq!(args.model, temp_dir.path().join("base.onnx"));
+        assert_eq!(args.max_det, 300);
+        assert!(args.annotate_cfg.show_box);
+    }
+
+    #[test]
+    fn test_parse_toml_layered_empty_paths_errors() {
+        assert!(parse_toml_layered(&[], Path::new("/tmp")).is_err());
+    }
+
+    #[test]
+    fn test_parse_toml_layered_carries_tracking_and_pipeline_fields() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let base_path = temp_dir.path().join("base.toml");
+        fs::write(
+            &base_path,
+            r#"
+[predict]
+model = "base.onnx"
+tracking = true
+track_max_age = 45
+track_iou_threshold = 0.4
+infer_workers = 3
+save_as_video = true
+"#,
+        )
+        .unwrap();
+
+        let override_path = temp_dir.path().join("override.toml");
+        fs::write(
+            &override_path,
+            r#"
+[predict]
+track_max_age = 60
+"#,
+        )
+        .unwrap();
+
+        let args = parse_toml_layered(&[&base_path, &override_path], temp_dir.path()).unwrap();
+
+        // Fields only set in the base layer persist across a layer that doesn't touch them.
+        assert!(args.tracking);
+        assert_eq!(args.track_iou_threshold, 0.4);
+        assert_eq!(args.infer_workers, 3);
+        assert!(args.save_as_video);
+
+        // Overridden field takes the later layer's value.
+        assert_eq!(args.track_max_age, 60);
+    }
 }
@@ -0,0 +1,473 @@
+//! SORT/ByteTrack-style multi-object tracker, layered on top of `sequential_infer` to assign
+//! persistent IDs to detections across frames (video/directory sources only — a single still
+//! image has nothing to track across).
+//!
+//! Each track holds a constant-velocity Kalman filter over `[cx, cy, s, r, vx, vy, vs]`, where
+//! `s` is box area and `r` is aspect ratio (assumed constant, so it has no velocity term, as in
+//! the original SORT paper). Association uses a greedy max-IoU match rather than the Hungarian
+//! algorithm — cheaper to implement correctly and, for the IoU-threshold-gated case used here,
+//! produces the same assignment in the vast majority of frames.
+
+use std::collections::HashSet;
+
+/// One frame's raw detection, as fed into [`Tracker::update`].
+#[derive(Debug, Clone, Copy)]
+pub struct Detection {
+    pub xyxy: [f32; 4],
+    pub conf: f32,
+}
+
+/// Tunables for [`Tracker`].
+#[derive(Debug, Clone, Copy)]
+pub struct TrackerConfig {
+    /// Frames a track may go unmatched before it's dropped.
+    pub max_age: usize,
+    /// Minimum IoU for a track/detection pair to be considered a match.
+    pub iou_threshold: f32,
+    /// Detections at or above this confidence are matched first and may start new tracks
+    /// (the ByteTrack "high" set).
+    pub high_conf_threshold: f32,
+    /// Detections in `[low_conf_threshold, high_conf_threshold)` are only used to recover
+    /// tracks left unmatched by the high-confidence pass; they never start a new track (the
+    /// ByteTrack "low" set).
+    pub low_conf_threshold: f32,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            max_age: 30,
+            iou_threshold: 0.3,
+            high_conf_threshold: 0.5,
+            low_conf_threshold: 0.1,
+        }
+    }
+}
+
+/// A single tracked object's persistent state.
+struct Track {
+    id: u64,
+    /// `[cx, cy, s, r, vx, vy, vs]`
+    x: Vec<f32>,
+    /// 7x7 state covariance.
+    p: Vec<Vec<f32>>,
+    time_since_update: usize,
+}
+
+impl Track {
+    fn new(id: u64, det: &Detection) -> Self {
+        let (cx, cy, s, r) = xyxy_to_state(det.xyxy);
+        let mut p = identity(7);
+        // Velocity components start with much higher uncertainty than the measured position.
+        for i in 4..7 {
+            p[i][i] = 1000.0;
+        }
+        Self {
+            id,
+            x: vec![cx, cy, s, r, 0.0, 0.0, 0.0],
+            p,
+            time_since_update: 0,
+        }
+    }
+
+    /// Constant-velocity motion update: `cx/cy/s` advance by their velocity; `r` is assumed
+    /// constant.
+    fn predict(&mut self) {
+        let mut f = identity(7);
+        f[0][4] = 1.0;
+        f[1][5] = 1.0;
+        f[2][6] = 1.0;
+
+        self.x = matvec(&f, &self.x);
+
+        // Process noise: larger on the velocity terms, which are the least certain part of the
+        // constant-velocity assumption.
+        let mut q = vec![vec![0.0; 7]; 7];
+        for (i, row) in q.iter_mut().enumerate() {
+            row[i] = if i < 4 { 1.0 } else { 10.0 };
+        }
+
+        let ft = transpose(&f);
+        self.p = mat_add(&matmul(&matmul(&f, &self.p), &ft), &q);
+        self.time_since_update += 1;
+    }
+
+    /// Kalman correction against an observed box.
+    fn update(&mut self, det: &Detection) {
+        let (cx, cy, s, r) = xyxy_to_state(det.xyxy);
+        let z = vec![cx, cy, s, r];
+
+        // H selects [cx, cy, s, r] out of the 7-element state.
+        let mut h = vec![vec![0.0; 7]; 4];
+        for i in 0..4 {
+            h[i][i] = 1.0;
+        }
+        let r_noise = identity(4); // measurement noise, in the same units as z
+
+        let hx = matvec(&h, &self.x);
+        let y: Vec<f32> = z.iter().zip(&hx).map(|(a, b)| a - b).collect();
+
+        let ht = transpose(&h);
+        let s_mat = mat_add(&matmul(&matmul(&h, &self.p), &ht), &r_noise);
+        let s_inv = invert4(&s_mat);
+        let k = matmul(&matmul(&self.p, &ht), &s_inv); // 7x4
+
+        let correction = matvec(&k, &y);
+        self.x = self.x.iter().zip(&correction).map(|(a, b)| a + b).collect();
+
+        let kh = matmul(&k, &h); // 7x7
+        let i7 = identity(7);
+        let i_minus_kh = mat_sub(&i7, &kh);
+        self.p = matmul(&i_minus_kh, &self.p);
+
+        self.time_since_update = 0;
+    }
+
+    fn predicted_xyxy(&self) -> [f32; 4] {
+        state_to_xyxy(self.x[0], self.x[1], self.x[2], self.x[3])
+    }
+}
+
+/// Multi-object tracker. Call [`Tracker::update`] once per frame with that frame's detections;
+/// it returns a track ID per detection, in the same order they were passed in.
+pub struct Tracker {
+    tracks: Vec<Track>,
+    next_id: u64,
+    config: TrackerConfig,
+}
+
+impl Tracker {
+    pub fn new(config: TrackerConfig) -> Self {
+        Self {
+            tracks: Vec::new(),
+            next_id: 1,
+            config,
+        }
+    }
+
+    /// Advance the tracker by one frame, returning a track ID aligned 1:1 with `detections`.
+    ///
+    /// Detections below `low_conf_threshold` never receive a persistent ID (they're assumed to
+    /// be noise ByteTrack wouldn't recover a track from either) — their slot is `0`, a sentinel
+    /// meaning "not tracked", since real track IDs start at `1`.
+    pub fn update(&mut self, detections: &[Detection]) -> Vec<u64> {
+        for track in &mut self.tracks {
+            track.predict();
+        }
+
+        let high_idx: Vec<usize> = detections
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.conf >= self.config.high_conf_threshold)
+            .map(|(i, _)| i)
+            .collect();
+        let low_idx: Vec<usize> = detections
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| {
+                d.conf >= self.config.low_conf_threshold && d.conf < self.config.high_conf_threshold
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut ids = vec![0u64; detections.len()];
+        let mut det_matched = vec![false; detections.len()];
+        let mut track_matched = vec![false; self.tracks.len()];
+
+        let all_tracks: Vec<usize> = (0..self.tracks.len()).collect();
+        for (t, d) in greedy_iou_match(&self.tracks, detections, &all_tracks, &high_idx, self.config.iou_threshold) {
+            self.tracks[t].update(&detections[d]);
+            ids[d] = self.tracks[t].id;
+            track_matched[t] = true;
+            det_matched[d] = true;
+        }
+
+        // ByteTrack: try to recover tracks the high-confidence pass left unmatched using the
+        // low-confidence detections.
+        let unmatched_tracks: Vec<usize> = (0..self.tracks.len()).filter(|&t| !track_matched[t]).collect();
+        for (t, d) in greedy_iou_match(&self.tracks, detections, &unmatched_tracks, &low_idx, self.config.iou_threshold) {
+            self.tracks[t].update(&detections[d]);
+            ids[d] = self.tracks[t].id;
+            track_matched[t] = true;
+            det_matched[d] = true;
+        }
+
+        // Unmatched high-confidence detections start new tracks; low-confidence ones don't.
+        for &d in &high_idx {
+            if !det_matched[d] {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.tracks.push(Track::new(id, &detections[d]));
+                ids[d] = id;
+                det_matched[d] = true;
+            }
+        }
+
+        self.tracks.retain(|t| t.time_since_update <= self.config.max_age);
+
+        ids
+    }
+}
+
+/// Greedy max-IoU assignment between `track_indices` and `det_indices`, gated by
+/// `iou_threshold`. Processes candidate pairs in descending IoU order so the strongest matches
+/// are claimed first, and never reuses a track or a detection once matched.
+fn greedy_iou_match(
+    tracks: &[Track],
+    detections: &[Detection],
+    track_indices: &[usize],
+    det_indices: &[usize],
+    iou_threshold: f32,
+) -> Vec<(usize, usize)> {
+    let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+    for &t in track_indices {
+        let track_box = tracks[t].predicted_xyxy();
+        for &d in det_indices {
+            let score = iou(track_box, detections[d].xyxy);
+            if score >= iou_threshold {
+                candidates.push((score, t, d));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut used_tracks = HashSet::new();
+    let mut used_dets = HashSet::new();
+    let mut matches = Vec::new();
+    for (_, t, d) in candidates {
+        if used_tracks.contains(&t) || used_dets.contains(&d) {
+            continue;
+        }
+        used_tracks.insert(t);
+        used_dets.insert(d);
+        matches.push((t, d));
+    }
+    matches
+}
+
+fn iou(a: [f32; 4], b: [f32; 4]) -> f32 {
+    let ix1 = a[0].max(b[0]);
+    let iy1 = a[1].max(b[1]);
+    let ix2 = a[2].min(b[2]);
+    let iy2 = a[3].min(b[3]);
+
+    let inter = (ix2 - ix1).max(0.0) * (iy2 - iy1).max(0.0);
+    let area_a = (a[2] - a[0]).max(0.0) * (a[3] - a[1]).max(0.0);
+    let area_b = (b[2] - b[0]).max(0.0) * (b[3] - b[1]).max(0.0);
+    let union = area_a + area_b - inter;
+
+    if union <= 0.0 { 0.0 } else { inter / union }
+}
+
+fn xyxy_to_state(xyxy: [f32; 4]) -> (f32, f32, f32, f32) {
+    let w = (xyxy[2] - xyxy[0]).max(1e-3);
+    let h = (xyxy[3] - xyxy[1]).max(1e-3);
+    let cx = xyxy[0] + w / 2.0;
+    let cy = xyxy[1] + h / 2.0;
+    (cx, cy, w * h, w / h)
+}
+
+fn state_to_xyxy(cx: f32, cy: f32, s: f32, r: f32) -> [f32; 4] {
+    let w = (s.max(0.0) * r.max(1e-3)).sqrt();
+    let h = if w > 0.0 { s / w } else { 0.0 };
+    [cx - w / 2.0, cy - h / 2.0, cx + w / 2.0, cy + h / 2.0]
+}
+
+// -- tiny matrix helpers (small, fixed dimensions only; not a general-purpose linear algebra lib)
+
+fn identity(n: usize) -> Vec<Vec<f32>> {
+    let mut m = vec![vec![0.0; n]; n];
+    for (i, row) in m.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+    m
+}
+
+fn transpose(a: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let rows = a.len();
+    let cols = a[0].len();
+    let mut out = vec![vec![0.0; rows]; cols];
+    for (i, row) in a.iter().enumerate() {
+        for (j, &v) in row.iter().enumerate() {
+            out[j][i] = v;
+        }
+    }
+    out
+}
+
+fn matmul(a: &[Vec<f32>], b: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let (rows, inner, cols) = (a.len(), b.len(), b[0].len());
+    let mut out = vec![vec![0.0; cols]; rows];
+    for (i, out_row) in out.iter_mut().enumerate() {
+        for (k, a_ik) in a[i].iter().enumerate().take(inner) {
+            for (j, out_ij) in out_row.iter_mut().enumerate().take(cols) {
+                *out_ij += a_ik * b[k][j];
+            }
+        }
+    }
+    out
+}
+
+fn matvec(a: &[Vec<f32>], v: &[f32]) -> Vec<f32> {
+    a.iter().map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum()).collect()
+}
+
+fn mat_add(a: &[Vec<f32>], b: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    a.iter()
+        .zip(b)
+        .map(|(ra, rb)| ra.iter().zip(rb).map(|(x, y)| x + y).collect())
+        .collect()
+}
+
+fn mat_sub(a: &[Vec<f32>], b: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    a.iter()
+        .zip(b)
+        .map(|(ra, rb)| ra.iter().zip(rb).map(|(x, y)| x - y).collect())
+        .collect()
+}
+
+/// Gauss-Jordan inverse of a 4x4 matrix (the innovation covariance is always 4x4 here, since the
+/// measurement is `[cx, cy, s, r]`). Adds a tiny epsilon to the diagonal first so a
+/// near-singular matrix doesn't blow up the result.
+fn invert4(m: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let n = 4;
+    let mut aug = vec![vec![0.0; 2 * n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            aug[i][j] = m[i][j] + if i == j { 1e-6 } else { 0.0 };
+        }
+        aug[i][n + i] = 1.0;
+    }
+
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|&a, &b| aug[a][col].abs().partial_cmp(&aug[b][col].abs()).unwrap())
+            .unwrap();
+        aug.swap(col, pivot_row);
+
+        let pivot = aug[col][col];
+        let pivot = if pivot.abs() < 1e-9 { 1e-9 } else { pivot };
+        for v in &mut aug[col] {
+            *v /= pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = aug[row][col];
+            for k in 0..2 * n {
+                aug[row][k] -= factor * aug[col][k];
+            }
+        }
+    }
+
+    aug.iter().map(|row| row[n..].to_vec()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "expected {b}, got {a}");
+    }
+
+    #[test]
+    fn invert4_matches_known_inverse() {
+        // A simple diagonal matrix has a trivially known inverse: 1/each diagonal entry.
+        let m = vec![
+            vec![2.0, 0.0, 0.0, 0.0],
+            vec![0.0, 4.0, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 0.0, 5.0],
+        ];
+        let inv = invert4(&m);
+
+        let expected = [0.5, 0.25, 1.0, 0.2];
+        for i in 0..4 {
+            for j in 0..4 {
+                let want = if i == j { expected[i] } else { 0.0 };
+                approx_eq(inv[i][j], want);
+            }
+        }
+    }
+
+    #[test]
+    fn invert4_of_identity_is_identity() {
+        let inv = invert4(&identity(4));
+        for i in 0..4 {
+            for j in 0..4 {
+                approx_eq(inv[i][j], if i == j { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    fn det(xyxy: [f32; 4]) -> Detection {
+        Detection { xyxy, conf: 0.9 }
+    }
+
+    #[test]
+    fn update_persists_track_id_across_frames() {
+        let mut tracker = Tracker::new(TrackerConfig::default());
+
+        // Same box, frame after frame: should be assigned the same persistent ID throughout.
+        let ids_1 = tracker.update(&[det([10.0, 10.0, 50.0, 50.0])]);
+        let ids_2 = tracker.update(&[det([11.0, 11.0, 51.0, 51.0])]);
+        let ids_3 = tracker.update(&[det([12.0, 12.0, 52.0, 52.0])]);
+
+        assert_ne!(ids_1[0], 0);
+        assert_eq!(ids_1[0], ids_2[0]);
+        assert_eq!(ids_2[0], ids_3[0]);
+    }
+
+    #[test]
+    fn update_ages_out_track_after_max_age_misses() {
+        let config = TrackerConfig {
+            max_age: 3,
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new(config);
+
+        let ids_1 = tracker.update(&[det([10.0, 10.0, 50.0, 50.0])]);
+        let original_id = ids_1[0];
+        assert_ne!(original_id, 0);
+
+        // Miss for exactly `max_age` frames: the track should still be alive on the last of
+        // those, since it's only dropped once `time_since_update` exceeds `max_age`.
+        for _ in 0..config.max_age {
+            tracker.update(&[]);
+        }
+
+        // One more miss pushes it over `max_age`; re-detecting the same box now must start a
+        // brand new track rather than reviving the old ID.
+        tracker.update(&[]);
+        let ids_after = tracker.update(&[det([10.0, 10.0, 50.0, 50.0])]);
+
+        assert_ne!(ids_after[0], 0);
+        assert_ne!(ids_after[0], original_id);
+    }
+
+    #[test]
+    fn update_never_assigns_colliding_ids_to_simultaneous_tracks() {
+        let mut tracker = Tracker::new(TrackerConfig::default());
+
+        // Two well-separated boxes in the same frame must start two distinct tracks.
+        let ids = tracker.update(&[
+            det([0.0, 0.0, 20.0, 20.0]),
+            det([200.0, 200.0, 260.0, 260.0]),
+        ]);
+        assert_ne!(ids[0], 0);
+        assert_ne!(ids[1], 0);
+        assert_ne!(ids[0], ids[1]);
+
+        // Advancing both boxes slightly should keep matching each to its own track, never
+        // swapping or colliding IDs.
+        let ids_next = tracker.update(&[
+            det([2.0, 2.0, 22.0, 22.0]),
+            det([202.0, 202.0, 262.0, 262.0]),
+        ]);
+        assert_eq!(ids_next[0], ids[0]);
+        assert_eq!(ids_next[1], ids[1]);
+    }
+}